@@ -0,0 +1,287 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    FnArg, ItemFn, Lifetime, LitStr, Pat, Path, ReturnType, Type, TypeReference, parse::Parser,
+    spanned::Spanned, visit_mut::VisitMut,
+};
+
+pub fn expand(args: TokenStream, mut item: ItemFn) -> syn::Result<TokenStream> {
+    let mut symbol = None;
+    let mut context = false;
+    let mut catch_panic = false;
+    if !args.is_empty() {
+        syn::meta::parser(|meta| {
+            if meta.path.is_ident("name") {
+                symbol = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else if meta.path.is_ident("context") {
+                context = true;
+                Ok(())
+            } else if meta.path.is_ident("catch_panic") {
+                catch_panic = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported dpi_import argument"))
+            }
+        })
+        .parse2(args)?;
+    }
+
+    let sig = &item.sig;
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            sig.generics.span(),
+            "dpi_import does not support generic functions",
+        ));
+    }
+    if let Some(token) = &sig.asyncness {
+        return Err(syn::Error::new(
+            token.span(),
+            "dpi_import does not support async functions",
+        ));
+    }
+    if let Some(variadic) = &sig.variadic {
+        return Err(syn::Error::new(
+            variadic.span(),
+            "dpi_import does not support variadic functions",
+        ));
+    }
+
+    let name = sig.ident.clone();
+    let symbol = symbol.unwrap_or_else(|| name.to_string());
+    let shim = format_ident!("__svdpi_shim_{}", name);
+    let signature = format_ident!("__svdpi_signature_{}", name);
+    let lt = Lifetime::new("'__svdpi", Span::call_site());
+    let static_lt = Lifetime::new("'static", Span::call_site());
+    let module = quote!(::svdpi::dpi::import);
+
+    let mut shim_params = Vec::new();
+    let mut prologue = Vec::new();
+    let mut call_args = Vec::new();
+    let mut epilogue = Vec::new();
+    let mut params = Vec::new();
+    let mut checks = Vec::new();
+
+    for (n, input) in item.sig.inputs.iter_mut().enumerate() {
+        let FnArg::Typed(input) = input else {
+            return Err(syn::Error::new(
+                input.span(),
+                "dpi_import does not support methods",
+            ));
+        };
+        let Pat::Ident(pat) = &*input.pat else {
+            return Err(syn::Error::new(
+                input.pat.span(),
+                "argument shall be an identifier",
+            ));
+        };
+        let param_name = pat.ident.to_string();
+        let inout = take_inout_attr(&mut input.attrs);
+        let abi = format_ident!("__svdpi_arg{}", n);
+        let value = format_ident!("__svdpi_value{}", n);
+
+        match &*input.ty {
+            Type::Reference(TypeReference {
+                mutability: Some(_),
+                elem,
+                ..
+            }) => {
+                let ty = with_lifetime(elem, &lt);
+                let out_trait = quote!(#module::DpiOutArg<#lt>);
+                shim_params.push(quote!(mut #abi: <#ty as #out_trait>::Abi));
+                if inout {
+                    prologue.push(quote! {
+                        #[cfg(debug_assertions)]
+                        <#ty as #out_trait>::check_abi(&#abi, #param_name);
+                        let mut #value = <#ty as #out_trait>::load(&#abi);
+                    });
+                } else {
+                    prologue.push(quote!(let mut #value = <#ty as #out_trait>::output();));
+                }
+                call_args.push(quote!(&mut #value));
+                epilogue.push(quote!(<#ty as #out_trait>::store(&#value, &mut #abi);));
+                let direction = if inout { quote!(Inout) } else { quote!(Output) };
+                let static_ty = with_lifetime(elem, &static_lt);
+                params.push((
+                    param_name.clone(),
+                    quote!(#module::Direction::#direction),
+                    type_name(elem),
+                    quote!(<#static_ty as #module::DpiOutArg<'static>>::SV_TYPE),
+                ));
+            }
+            ty => {
+                let static_ty = with_lifetime(ty, &static_lt);
+                let static_trait = quote!(#module::DpiArg<'static>);
+                // `InoutBV` and `InoutLV` are aliases of `OutBV` and `OutLV`
+                let direction = if inout {
+                    let msg = format!("`{}` is not an `inout` argument", type_name(ty));
+                    checks.push(quote! {
+                        assert!(
+                            !matches!(
+                                <#static_ty as #static_trait>::DIRECTION,
+                                #module::Direction::Input
+                            ),
+                            #msg
+                        );
+                    });
+                    quote!(#module::Direction::Inout)
+                } else {
+                    quote!(<#static_ty as #static_trait>::DIRECTION)
+                };
+                params.push((
+                    param_name.clone(),
+                    direction,
+                    type_name(ty),
+                    quote!(<#static_ty as #static_trait>::SV_TYPE),
+                ));
+
+                let ty = with_lifetime(ty, &lt);
+                let arg_trait = quote!(#module::DpiArg<#lt>);
+                shim_params.push(quote!(#abi: <#ty as #arg_trait>::Abi));
+                prologue.push(quote! {
+                    #[cfg(debug_assertions)]
+                    <#ty as #arg_trait>::check_abi(&#abi, #param_name);
+                });
+                call_args.push(quote!(<#ty as #arg_trait>::from_abi(#abi)));
+            }
+        }
+    }
+
+    let (ret_ty, ret_name) = match &item.sig.output {
+        ReturnType::Default => (quote!(()), quote!(None)),
+        ReturnType::Type(_, ty) => {
+            let ty_name = type_name(ty);
+            (quote!(#ty), quote!(Some(#ty_name)))
+        }
+    };
+    let call = match &item.sig.unsafety {
+        Some(_) => quote!(unsafe { #name(#(#call_args),*) }),
+        None => quote!(#name(#(#call_args),*)),
+    };
+    let body = quote! {
+        let _call = ::svdpi::dpi::begin_call();
+        #(#prologue)*
+        let ret = #call;
+        #(#epilogue)*
+        <#ret_ty as #module::DpiRet>::into_abi(ret)
+    };
+    let body = match catch_panic {
+        true => quote!(#module::catch_panic_abi(move || { #body })),
+        false => body,
+    };
+    let vis = &item.vis;
+    let param_descs = params.iter().map(|(name, direction, ty, sv_type)| {
+        quote! {
+            #module::DpiParam {
+                name: #name,
+                direction: #direction,
+                ty: #ty,
+                sv_type: #sv_type,
+            }
+        }
+    });
+
+    Ok(quote! {
+        #item
+
+        #[doc(hidden)]
+        #[unsafe(export_name = #symbol)]
+        extern "C" fn #shim<#lt>(#(#shim_params),*) -> <#ret_ty as #module::DpiRet>::Abi {
+            #body
+        }
+
+        const _: () = {
+            #(#checks)*
+        };
+
+        #[doc(hidden)]
+        #[allow(non_upper_case_globals)]
+        #vis const #signature: #module::DpiSignature = #module::DpiSignature {
+            name: #symbol,
+            context: #context,
+            params: &[#(#param_descs),*],
+            ret: #ret_name,
+            sv_ret: <#ret_ty as #module::DpiRet>::SV_TYPE,
+            task: <#ret_ty as #module::DpiRet>::IS_TASK,
+        };
+
+        const _: () = {
+            static REGISTRATION: #module::Registration = #module::Registration::new(&#signature);
+
+            extern "C" fn register() {
+                #module::register(&REGISTRATION);
+            }
+
+            // run when the program or library is loaded, see `registered_signatures`
+            #[used]
+            #[cfg_attr(
+                any(target_os = "linux", target_os = "android", target_os = "freebsd"),
+                unsafe(link_section = ".init_array")
+            )]
+            #[cfg_attr(target_vendor = "apple", unsafe(link_section = "__DATA,__mod_init_func"))]
+            #[cfg_attr(windows, unsafe(link_section = ".CRT$XCU"))]
+            static REGISTER: extern "C" fn() = register;
+        };
+    })
+}
+
+/// `dpi_signature!(path::to::foo)` names the signature constant next to `foo`
+pub fn signature_path(mut path: Path) -> syn::Result<TokenStream> {
+    let span = path.span();
+    let last = path
+        .segments
+        .last_mut()
+        .ok_or_else(|| syn::Error::new(span, "expect a function path"))?;
+    last.ident = format_ident!("__svdpi_signature_{}", last.ident);
+    Ok(quote!(&#path))
+}
+
+fn take_inout_attr(attrs: &mut Vec<syn::Attribute>) -> bool {
+    let len = attrs.len();
+    attrs.retain(|attr| !attr.path().is_ident("inout"));
+    attrs.len() != len
+}
+
+// replace elided and anonymous lifetimes with the lifetime of the shim,
+// so that `&str` and `&CStr` arguments borrow from the call
+struct ElidedLifetime<'a>(&'a Lifetime);
+
+impl VisitMut for ElidedLifetime<'_> {
+    fn visit_type_reference_mut(&mut self, ty: &mut TypeReference) {
+        if ty.lifetime.is_none() {
+            ty.lifetime = Some(self.0.clone());
+        }
+        syn::visit_mut::visit_type_reference_mut(self, ty);
+    }
+
+    fn visit_lifetime_mut(&mut self, lt: &mut Lifetime) {
+        if lt.ident == "_" {
+            *lt = self.0.clone();
+        }
+    }
+}
+
+fn with_lifetime(ty: &Type, lt: &Lifetime) -> Type {
+    let mut ty = ty.clone();
+    ElidedLifetime(lt).visit_type_mut(&mut ty);
+    ty
+}
+
+// the type as written, with spaces only where needed between identifiers
+fn type_name(ty: &Type) -> String {
+    let tokens = quote!(#ty).to_string();
+    let chars: Vec<char> = tokens.chars().collect();
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut name = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == ' ' {
+            let prev = name.chars().last();
+            let next = chars.get(i + 1).copied();
+            if !(prev.is_some_and(is_word) && next.is_some_and(is_word)) {
+                continue;
+            }
+        }
+        name.push(c);
+    }
+    name.replace(',', ", ")
+}
@@ -0,0 +1,76 @@
+//! Procedural macros for `svdpi`. Use them through `svdpi` with `macros` feature enabled.
+
+use proc_macro::TokenStream;
+
+mod import;
+mod packed;
+mod transparent;
+
+/// Derive `SvPackedField` and `SvPacked` for a struct with named fields.
+///
+/// Fields are laid out MSB first, the first field occupies the most significant bits.
+/// Use `#[bits(N)]` to narrow an integer or `BitVec` field to `N` bits, other fields
+/// such as `bool` or nested packed structs cannot be narrowed.
+#[proc_macro_derive(SvPacked, attributes(bits))]
+pub fn derive_sv_packed(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    packed::derive(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `SvTransparent` for a fieldless enum with an integer repr, e.g. `#[repr(u8)]`.
+///
+/// The enum is passed as its repr type. An encoding not matching any variant decodes to
+/// the variant marked `#[fallback]`, or panics if there is none. An `output` argument
+/// starts from encoding `0`, so an enum without a fallback shall have a variant of `0`.
+#[proc_macro_derive(SvEnum, attributes(fallback))]
+pub fn derive_sv_enum(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    transparent::derive_enum(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `SvTransparent` for a `#[repr(transparent)]` struct with a single field,
+/// which is passed as the type of the field.
+#[proc_macro_derive(SvNewtype)]
+pub fn derive_sv_newtype(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    transparent::derive_newtype(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generate an `extern "C"` DPI import shim for a plain Rust function.
+///
+/// Arguments taken by value are `input` arguments, `&mut T` arguments are `output`, or `inout`
+/// if marked with `#[inout]`. The shim is exported under the function name, or the name given
+/// by `#[dpi_import(name = "...")]`. The function itself is kept and callable from Rust.
+/// Its signature is recorded, get it with `dpi_signature!`, and registered at load time for
+/// `svdpi::dpi::codegen::SvPackage::registered`. Mark functions calling back into
+/// the simulator with `#[dpi_import(context)]`, so the generated declaration is `context`.
+///
+/// The return type is `()`, a basic type such as `u32` or an `SvEnum`, `Option<bool>`
+/// returned as `logic` with `None` as X, a `BitVec` up to 32 bits, a string, or
+/// `Result<_, Disabled>`, see `svdpi::dpi::import::DpiRet`. Other `Option<T>`, wider and
+/// `logic` vectors cannot be returned, pass them by an `output` argument.
+///
+/// With `#[dpi_import(catch_panic)]`, a panic in the shim is reported and terminates the
+/// simulation instead of unwinding into the simulator, see `svdpi::dpi::catch_panic`.
+#[proc_macro_attribute]
+pub fn dpi_import(args: TokenStream, item: TokenStream) -> TokenStream {
+    let item = syn::parse_macro_input!(item as syn::ItemFn);
+    import::expand(args.into(), item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Get the `&DpiSignature` recorded by `#[dpi_import]`, e.g. `dpi_signature!(path::to::foo)`
+#[proc_macro]
+pub fn dpi_signature(input: TokenStream) -> TokenStream {
+    let path = syn::parse_macro_input!(input as syn::Path);
+    import::signature_path(path)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,101 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitInt, spanned::Spanned};
+
+pub fn derive(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "SvPacked does not support generic structs",
+        ));
+    }
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "SvPacked requires a struct with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "SvPacked can only be derived for structs",
+            ));
+        }
+    };
+
+    let field_trait = quote!(::svdpi::dpi::packed::SvPackedField);
+
+    let mut idents = Vec::new();
+    let mut widths = Vec::new();
+    let mut checks = Vec::new();
+    for field in fields {
+        let ty = &field.ty;
+        let mut width = quote!(<#ty as #field_trait>::WIDTH);
+        for attr in &field.attrs {
+            if !attr.path().is_ident("bits") {
+                continue;
+            }
+            let lit: LitInt = attr.parse_args()?;
+            if lit.base10_parse::<usize>()? == 0 {
+                return Err(syn::Error::new(lit.span(), "field width shall be positive"));
+            }
+            let narrow_msg = format!(
+                "`{}` is narrower than {} bits",
+                quote!(#ty),
+                lit.base10_digits()
+            );
+            let width_msg = format!(
+                "`{}` cannot be narrowed, `#[bits]` shall equal its width",
+                quote!(#ty)
+            );
+            checks.push(quote! {
+                // the type may contain braces, e.g. `BitVec<{ 4 + 4 }>`, not a format string
+                assert!(#lit <= <#ty as #field_trait>::WIDTH, "{}", #narrow_msg);
+                assert!(
+                    <#ty as #field_trait>::NARROWABLE || #lit == <#ty as #field_trait>::WIDTH,
+                    "{}",
+                    #width_msg
+                );
+            });
+            width = quote!(#lit);
+        }
+        idents.push(field.ident.clone().unwrap());
+        widths.push(width);
+    }
+
+    // fields are MSB first, so a field's LSB is the total width of the fields after it
+    let lsbs: Vec<_> = (0..widths.len())
+        .map(|i| {
+            let after = &widths[i + 1..];
+            quote!(0 #(+ #after)*)
+        })
+        .collect();
+    let types = fields.iter().map(|field| &field.ty);
+
+    Ok(quote! {
+        const _: () = {
+            #(#checks)*
+        };
+
+        impl #field_trait for #name {
+            const WIDTH: usize = 0 #(+ #widths)*;
+
+            fn read_bits(words: &[u32], lsb: usize, _width: usize) -> Self {
+                Self {
+                    #(#idents: <#types as #field_trait>::read_bits(words, lsb + #lsbs, #widths),)*
+                }
+            }
+
+            fn write_bits(&self, words: &mut [u32], lsb: usize, _width: usize) {
+                #(#field_trait::write_bits(&self.#idents, words, lsb + #lsbs, #widths);)*
+            }
+        }
+
+        impl ::svdpi::dpi::packed::SvPacked for #name {}
+    })
+}
@@ -0,0 +1,163 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Attribute, Data, DeriveInput, Fields, Ident, spanned::Spanned};
+
+const ENUM_REPRS: [&str; 8] = ["u8", "i8", "u16", "i16", "u32", "i32", "u64", "i64"];
+
+pub fn derive_enum(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "SvEnum does not support generic enums",
+        ));
+    }
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "SvEnum can only be derived for enums",
+        ));
+    };
+    let repr = find_repr(&input.attrs, |ident| {
+        ENUM_REPRS.iter().any(|repr| ident == repr)
+    })
+    .ok_or_else(|| {
+        syn::Error::new(
+            input.span(),
+            "SvEnum requires an integer repr, e.g. `#[repr(u8)]`",
+        )
+    })?;
+
+    let mut variants = Vec::new();
+    let mut fallback = None;
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new(
+                variant.fields.span(),
+                "SvEnum requires a fieldless enum",
+            ));
+        }
+        if variant
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("fallback"))
+        {
+            if fallback.is_some() {
+                return Err(syn::Error::new(
+                    variant.span(),
+                    "at most one variant can be `#[fallback]`",
+                ));
+            }
+            fallback = Some(&variant.ident);
+        }
+        variants.push(&variant.ident);
+    }
+
+    let consts: Vec<_> = (0..variants.len())
+        .map(|n| format_ident!("__SVDPI_VARIANT{}", n))
+        .collect();
+    let (other, zero_check) = match fallback {
+        Some(variant) => (quote!(#name::#variant), quote!()),
+        None => {
+            let ty = name.to_string();
+            // `output` arguments start from encoding 0, which shall decode without panicking
+            let msg = format!("SvEnum `{ty}` requires a variant of `0` or a `#[fallback]`");
+            (
+                quote!(::svdpi::dpi::param::invalid_enum_encoding(#ty, repr)),
+                quote! {
+                    const _: () = assert!(false #(|| #name::#variants as #repr == 0)*, #msg);
+                },
+            )
+        }
+    };
+
+    Ok(quote! {
+        unsafe impl ::svdpi::dpi::param::SvTransparent for #name {
+            type Repr = #repr;
+
+            fn from_repr(repr: #repr) -> Self {
+                Self::try_from_repr(repr).unwrap_or_else(|| #other)
+            }
+
+            fn try_from_repr(repr: #repr) -> Option<Self> {
+                #(const #consts: #repr = #name::#variants as #repr;)*
+                #[allow(unreachable_patterns)]
+                match repr {
+                    #(#consts => Some(#name::#variants),)*
+                    _ => None,
+                }
+            }
+
+            fn to_repr(self) -> #repr {
+                self as #repr
+            }
+        }
+
+        ::svdpi::__impl_export_arg_basic!([] #name);
+
+        #zero_check
+    })
+}
+
+pub fn derive_newtype(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "SvNewtype does not support generic structs",
+        ));
+    }
+    let fields = match &input.data {
+        Data::Struct(data) if data.fields.len() == 1 => &data.fields,
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "SvNewtype requires a struct with exactly one field",
+            ));
+        }
+    };
+    if find_repr(&input.attrs, |ident| ident == "transparent").is_none() {
+        return Err(syn::Error::new(
+            input.span(),
+            "SvNewtype requires `#[repr(transparent)]`",
+        ));
+    }
+
+    let field = fields.iter().next().unwrap();
+    let ty = &field.ty;
+    let (construct, access) = match &field.ident {
+        Some(ident) => (quote!(#name { #ident: repr }), quote!(self.#ident)),
+        None => (quote!(#name(repr)), quote!(self.0)),
+    };
+
+    Ok(quote! {
+        unsafe impl ::svdpi::dpi::param::SvTransparent for #name {
+            type Repr = #ty;
+
+            fn from_repr(repr: #ty) -> Self {
+                #construct
+            }
+
+            fn to_repr(self) -> #ty {
+                #access
+            }
+        }
+
+        ::svdpi::__impl_export_arg_basic!([] #name);
+    })
+}
+
+// the first `#[repr(..)]` item accepted by `accept`
+fn find_repr(attrs: &[Attribute], accept: impl Fn(&Ident) -> bool) -> Option<Ident> {
+    let mut found = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            match meta.path.get_ident() {
+                Some(ident) if found.is_none() && accept(ident) => found = Some(ident.clone()),
+                _ => {}
+            }
+            Ok(())
+        });
+    }
+    found
+}
@@ -1,10 +1,39 @@
 use crate::sys::dpi as sys;
 use std::{
     ffi::{CStr, CString, c_char, c_void},
+    ops::{BitAnd, BitOr, BitXor, Not},
     ptr::{self, NonNull},
 };
 
+mod bits;
+mod bitvec;
+mod caller;
+mod chandle;
+/// Generating `import "DPI-C"` declarations from `#[dpi_import]` functions
+pub mod codegen;
+/// Calling SystemVerilog exported functions and tasks
+pub mod export;
+/// Support for `#[dpi_import]` functions
+pub mod import;
+mod instance;
+mod literal;
+mod logicvec;
+/// Mapping Rust structs onto SystemVerilog packed structs
+pub mod packed;
 pub mod param;
+mod str_arena;
+mod user_data;
+
+pub use bitvec::{BitVec, OutOfRangeError};
+pub use caller::{
+    CallSite, CallerInfo, caller_info, catch_panic, install_panic_hook, report_error,
+};
+pub use chandle::Chandle;
+pub use instance::InstanceRegistry;
+pub use literal::ParseLiteralError;
+pub use logicvec::LogicVec;
+pub use str_arena::{CallGuard, begin_call};
+pub use user_data::{UserDataKey, drop_all_user_data};
 
 /// See also [`sys::sv_0`]
 pub const SV_0: u8 = 0;
@@ -72,6 +101,77 @@ impl Logic {
             None => Logic::Z,
         }
     }
+
+    /// Decode from `aval` / `bval` bits. See also [`sys::svLogicVecVal`]
+    pub fn from_avbv(aval: bool, bval: bool) -> Self {
+        match (aval, bval) {
+            (false, false) => Logic::Value0,
+            (true, false) => Logic::Value1,
+            (false, true) => Logic::Z,
+            (true, true) => Logic::X,
+        }
+    }
+
+    /// Encode into `(aval, bval)` bits. See also [`sys::svLogicVecVal`]
+    pub fn to_avbv(self) -> (bool, bool) {
+        match self {
+            Logic::Value0 => (false, false),
+            Logic::Value1 => (true, false),
+            Logic::Z => (false, true),
+            Logic::X => (true, true),
+        }
+    }
+
+    /// Whether the value is X or Z
+    pub fn is_unknown(self) -> bool {
+        matches!(self, Logic::X | Logic::Z)
+    }
+}
+
+/// X and Z are inverted to X
+impl Not for Logic {
+    type Output = Logic;
+    fn not(self) -> Logic {
+        match self {
+            Logic::Value0 => Logic::Value1,
+            Logic::Value1 => Logic::Value0,
+            _ => Logic::X,
+        }
+    }
+}
+
+/// `0` wins over X and Z
+impl BitAnd for Logic {
+    type Output = Logic;
+    fn bitand(self, rhs: Logic) -> Logic {
+        match (self, rhs) {
+            (Logic::Value0, _) | (_, Logic::Value0) => Logic::Value0,
+            (Logic::Value1, Logic::Value1) => Logic::Value1,
+            _ => Logic::X,
+        }
+    }
+}
+
+/// `1` wins over X and Z
+impl BitOr for Logic {
+    type Output = Logic;
+    fn bitor(self, rhs: Logic) -> Logic {
+        match (self, rhs) {
+            (Logic::Value1, _) | (_, Logic::Value1) => Logic::Value1,
+            (Logic::Value0, Logic::Value0) => Logic::Value0,
+            _ => Logic::X,
+        }
+    }
+}
+
+impl BitXor for Logic {
+    type Output = Logic;
+    fn bitxor(self, rhs: Logic) -> Logic {
+        match (self.into_bool(), rhs.into_bool()) {
+            (Some(a), Some(b)) => (a ^ b).into(),
+            _ => Logic::X,
+        }
+    }
 }
 
 /// Get current simulation time in _simulation time unit_. See also [`sys::svGetTime`]
@@ -95,6 +195,49 @@ pub fn get_time() -> u64 {
     ((time.high as u64) << 32) + (time.low as u64)
 }
 
+/// Simulation time precision exponent, i.e. the unit of [`get_time`].
+/// See also [`sys::svGetTimePrecision`]
+///
+/// # Panics
+///
+/// Panics if underlying `svGetTimePrecision` fails
+#[cfg(feature = "sv2023")]
+pub fn time_precision() -> i8 {
+    let mut precision = 0;
+    unsafe {
+        let ret = sys::svGetTimePrecision(ptr::null_mut(), &mut precision);
+        assert!(ret == 0, "svGetTimePrecision failed");
+    }
+    precision as i8
+}
+
+/// Time unit exponent of `scope`. See also [`sys::svGetTimeUnit`]
+///
+/// # Panics
+///
+/// Panics if underlying `svGetTimeUnit` fails
+#[cfg(feature = "sv2023")]
+pub fn time_unit(scope: SvScope) -> i8 {
+    let mut unit = 0;
+    unsafe {
+        let ret = sys::svGetTimeUnit(scope.ptr.as_ptr(), &mut unit);
+        assert!(ret == 0, "svGetTimeUnit failed");
+    }
+    unit as i8
+}
+
+/// Get current simulation time in simulation time precision
+#[cfg(feature = "sv2023")]
+pub fn get_sim_time() -> crate::SimTime {
+    crate::SimTime::new(get_time(), time_precision())
+}
+
+/// Get current simulation time in the time unit of `scope`, truncated
+#[cfg(feature = "sv2023")]
+pub fn get_time_in(scope: SvScope) -> crate::SimTime {
+    get_sim_time().to_unit(time_unit(scope))
+}
+
 #[deprecated]
 /// Equivalent to `SvScope::from_name(name).unwrap().make_current()`
 pub fn set_scope_by_name(name: &str) {
@@ -194,17 +337,70 @@ impl SvScope {
         Self::set_current(Some(self));
     }
 
+    /// Set current context until the returned guard is dropped, see [`ScopeGuard`]
+    pub fn enter(self) -> ScopeGuard {
+        let prev = Self::swap_current(Some(self));
+        ScopeGuard::new(prev)
+    }
+
     /// Execute code with current context set temporarily, then restore the original context.
     ///
     /// # Unwind Behaviour
     ///
-    /// If `f()` panics, the previous context will NOT be restores.
-    ///
-    /// This is the current behaviour. It may change in the future.
+    /// If `f()` panics, the previous context is restored while unwinding, see [`Self::enter`].
     pub fn with_current<R>(self, f: impl FnOnce() -> R) -> R {
-        let prev = Self::swap_current(Some(self));
-        let ret = f();
-        Self::set_current(prev);
-        ret
+        let _guard = self.enter();
+        f()
+    }
+}
+
+#[cfg(debug_assertions)]
+thread_local! {
+    static SCOPE_DEPTH: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Restores the previous context on drop, also on unwind. Created by [`SvScope::enter`]
+///
+/// Guards may nest, and shall be dropped in reverse order of creation, which is checked in
+/// debug builds. A leaked guard never restores its context.
+#[must_use = "the previous context is restored when the guard is dropped"]
+#[derive(Debug)]
+pub struct ScopeGuard {
+    prev: Option<SvScope>,
+    #[cfg(debug_assertions)]
+    depth: usize,
+    // the current context is per thread
+    _not_send: std::marker::PhantomData<*const ()>,
+}
+
+impl ScopeGuard {
+    fn new(prev: Option<SvScope>) -> Self {
+        ScopeGuard {
+            prev,
+            #[cfg(debug_assertions)]
+            depth: SCOPE_DEPTH.with(|depth| {
+                depth.set(depth.get() + 1);
+                depth.get()
+            }),
+            _not_send: std::marker::PhantomData,
+        }
+    }
+
+    /// The context restored on drop
+    pub fn prev(&self) -> Option<SvScope> {
+        self.prev
+    }
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        SCOPE_DEPTH.with(|depth| {
+            if depth.get() != self.depth && !std::thread::panicking() {
+                panic!("ScopeGuard dropped out of order");
+            }
+            depth.set(self.depth - 1);
+        });
+        SvScope::set_current(self.prev);
     }
 }
@@ -0,0 +1,114 @@
+// Word-level helpers shared by packed vector views and owned vectors.
+// Vectors are stored as `u32` words, LSB first, as `svBitVecVal` does.
+
+/// Mask of valid bits in the last word of a `bits`-bit vector
+pub(crate) const fn last_word_mask(bits: usize) -> u32 {
+    match bits % 32 {
+        0 => !0,
+        n => (1 << n) - 1,
+    }
+}
+
+/// Mask of the low `width` bits, `width` shall be in `0..=32`
+pub(crate) const fn low_mask(width: usize) -> u32 {
+    match width {
+        32 => !0,
+        n => (1 << n) - 1,
+    }
+}
+
+/// Read 32 bits starting at bit `pos`. Bits out of `words` read as zero.
+pub(crate) fn read_u32(words: &[u32], pos: isize) -> u32 {
+    if pos <= -32 {
+        return 0;
+    }
+    if pos < 0 {
+        return words.first().copied().unwrap_or(0) << -pos;
+    }
+    let (w, s) = (pos as usize / 32, pos as usize % 32);
+    let lo = words.get(w).copied().unwrap_or(0) >> s;
+    let hi = match s {
+        0 => 0,
+        s => words.get(w + 1).copied().unwrap_or(0) << (32 - s),
+    };
+    lo | hi
+}
+
+/// Write low `width` bits of `value` at bit `pos`, `width` shall be in `1..=32`
+pub(crate) fn write_bits(words: &mut [u32], pos: usize, width: usize, value: u32) {
+    let mask = low_mask(width);
+    let value = value & mask;
+    let (w, s) = (pos / 32, pos % 32);
+    words[w] = (words[w] & !(mask << s)) | (value << s);
+    if s != 0 && s + width > 32 {
+        words[w + 1] = (words[w + 1] & !(mask >> (32 - s))) | (value >> (32 - s));
+    }
+}
+
+/// Read `width` bits starting at `pos` into `dst`, `dst` shall hold exactly `width` bits
+pub(crate) fn read_range(src: &[u32], pos: usize, width: usize, dst: &mut [u32]) {
+    for (i, word) in dst.iter_mut().enumerate() {
+        *word = read_u32(src, (pos + 32 * i) as isize);
+    }
+    if let Some(last) = dst.last_mut() {
+        *last &= last_word_mask(width);
+    }
+}
+
+/// Write `width` bits of `src` at `pos`
+pub(crate) fn write_range(dst: &mut [u32], pos: usize, width: usize, src: &[u32]) {
+    for (i, &word) in src.iter().enumerate() {
+        let offset = 32 * i;
+        if offset >= width {
+            break;
+        }
+        write_bits(dst, pos + offset, (width - offset).min(32), word);
+    }
+}
+
+// implement `op` for all combinations of owned and borrowed operands, and `op_assign`,
+// by calling `$V::$method(&self, &rhs)`
+macro_rules! impl_binary_op {
+    ($V: ident, $Op: ident, $op: ident, $OpAssign: ident, $op_assign: ident, $method: ident) => {
+        impl<const BITS: usize> $Op<&$V<BITS>> for &$V<BITS> {
+            type Output = $V<BITS>;
+            fn $op(self, rhs: &$V<BITS>) -> $V<BITS> {
+                self.$method(rhs)
+            }
+        }
+
+        impl<const BITS: usize> $Op<$V<BITS>> for &$V<BITS> {
+            type Output = $V<BITS>;
+            fn $op(self, rhs: $V<BITS>) -> $V<BITS> {
+                self.$op(&rhs)
+            }
+        }
+
+        impl<const BITS: usize> $Op<&$V<BITS>> for $V<BITS> {
+            type Output = $V<BITS>;
+            fn $op(self, rhs: &$V<BITS>) -> $V<BITS> {
+                (&self).$op(rhs)
+            }
+        }
+
+        impl<const BITS: usize> $Op<$V<BITS>> for $V<BITS> {
+            type Output = $V<BITS>;
+            fn $op(self, rhs: $V<BITS>) -> $V<BITS> {
+                (&self).$op(&rhs)
+            }
+        }
+
+        impl<const BITS: usize> $OpAssign<&$V<BITS>> for $V<BITS> {
+            fn $op_assign(&mut self, rhs: &$V<BITS>) {
+                *self = (&*self).$op(rhs);
+            }
+        }
+
+        impl<const BITS: usize> $OpAssign<$V<BITS>> for $V<BITS> {
+            fn $op_assign(&mut self, rhs: $V<BITS>) {
+                *self = (&*self).$op(&rhs);
+            }
+        }
+    };
+}
+pub(crate) use impl_binary_op;
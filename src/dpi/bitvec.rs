@@ -0,0 +1,607 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{
+        Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Mul,
+        MulAssign, Neg, Not, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign,
+    },
+};
+
+use super::bits::{self, impl_binary_op};
+
+// vectors up to 128 bits are stored inline
+const INLINE_WORDS: usize = 4;
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Words {
+    Inline([u32; INLINE_WORDS]),
+    Heap(Box<[u32]>),
+}
+
+/// Owned two-state packed vector, the value counterpart of `bit [BITS-1:0]`
+///
+/// Stored as `u32` words LSB first, in the same layout as [`super::param::InBV`].
+/// Unused top bits of the last word are always zero.
+///
+/// Arithmetic operators wrap around at `BITS` bits, as SystemVerilog does.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BitVec<const BITS: usize> {
+    words: Words,
+}
+
+/// Error returned when a [`BitVec`] does not fit into the target integer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfRangeError;
+
+impl fmt::Display for OutOfRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("bit vector value out of range of target type")
+    }
+}
+
+impl std::error::Error for OutOfRangeError {}
+
+impl<const BITS: usize> BitVec<BITS> {
+    /// Number of `u32` words
+    pub const U32_LEN: usize = BITS.div_ceil(32);
+
+    /// All bits are zero
+    pub fn zero() -> Self {
+        let words = if Self::U32_LEN <= INLINE_WORDS {
+            Words::Inline([0; INLINE_WORDS])
+        } else {
+            Words::Heap(vec![0; Self::U32_LEN].into_boxed_slice())
+        };
+        Self { words }
+    }
+
+    /// All bits are one
+    pub fn ones() -> Self {
+        !Self::zero()
+    }
+
+    /// Build from words LSB first, unused top bits are ignored
+    ///
+    /// # Panics
+    ///
+    /// Panics if `words.len()` mismatches
+    pub fn from_words(words: &[u32]) -> Self {
+        assert_eq!(words.len(), Self::U32_LEN);
+        let mut value = Self::zero();
+        value.words_mut().copy_from_slice(words);
+        value.canonicalize();
+        value
+    }
+
+    /// Words LSB first, unused top bits are zero
+    pub fn as_words(&self) -> &[u32] {
+        match &self.words {
+            Words::Inline(words) => &words[..Self::U32_LEN],
+            Words::Heap(words) => words,
+        }
+    }
+
+    /// Build from bytes LSB first, unused top bits are ignored
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes.len()` is not `BITS.div_ceil(8)`
+    pub fn from_le_bytes(bytes: &[u8]) -> Self {
+        assert_eq!(bytes.len(), BITS.div_ceil(8));
+        let mut value = Self::zero();
+        for (word, chunk) in value.words_mut().iter_mut().zip(bytes.chunks(4)) {
+            let mut buf = [0; 4];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            *word = u32::from_le_bytes(buf);
+        }
+        value.canonicalize();
+        value
+    }
+
+    /// Bytes LSB first, `BITS.div_ceil(8)` in total
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        let len = BITS.div_ceil(8);
+        self.as_words()
+            .iter()
+            .flat_map(|w| w.to_le_bytes())
+            .take(len)
+            .collect()
+    }
+
+    // callers shall call `canonicalize` after modification
+    fn words_mut(&mut self) -> &mut [u32] {
+        match &mut self.words {
+            Words::Inline(words) => &mut words[..Self::U32_LEN],
+            Words::Heap(words) => words,
+        }
+    }
+
+    fn canonicalize(&mut self) {
+        if let Some(last) = self.words_mut().last_mut() {
+            *last &= bits::last_word_mask(BITS);
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `i >= BITS`
+    pub fn bit(&self, i: usize) -> bool {
+        assert!(i < BITS, "bit index {i} out of range for {BITS}-bit vector");
+        (self.as_words()[i / 32] >> (i % 32)) & 1 != 0
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `i >= BITS`
+    pub fn set_bit(&mut self, i: usize, value: bool) {
+        assert!(i < BITS, "bit index {i} out of range for {BITS}-bit vector");
+        bits::write_bits(self.words_mut(), i, 1, value as u32);
+    }
+
+    /// Part select `[lsb +: W]`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lsb + W > BITS`
+    pub fn part<const W: usize>(&self, lsb: usize) -> BitVec<W> {
+        assert!(
+            lsb + W <= BITS,
+            "part select [{lsb} +: {W}] out of range for {BITS}-bit vector"
+        );
+        let mut value = BitVec::<W>::zero();
+        bits::read_range(self.as_words(), lsb, W, value.words_mut());
+        value
+    }
+
+    /// Assign part select `[lsb +: W]`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lsb + W > BITS`
+    pub fn set_part<const W: usize>(&mut self, lsb: usize, value: &BitVec<W>) {
+        assert!(
+            lsb + W <= BITS,
+            "part select [{lsb} +: {W}] out of range for {BITS}-bit vector"
+        );
+        bits::write_range(self.words_mut(), lsb, W, value.as_words());
+    }
+
+    /// Zero-extend or truncate to another width
+    pub fn resize<const W: usize>(&self) -> BitVec<W> {
+        let mut value = BitVec::<W>::zero();
+        bits::read_range(self.as_words(), 0, W, value.words_mut());
+        value
+    }
+
+    /// Sign-extend or truncate to another width
+    pub fn resize_signed<const W: usize>(&self) -> BitVec<W> {
+        let mut value = self.resize::<W>();
+        if W > BITS && self.is_negative() {
+            for i in BITS..W {
+                value.set_bit(i, true);
+            }
+        }
+        value
+    }
+
+    /// Number of bits set to one
+    pub fn count_ones(&self) -> u32 {
+        self.as_words().iter().map(|w| w.count_ones()).sum()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.as_words().iter().all(|&w| w == 0)
+    }
+
+    /// Whether MSB is set, i.e. the value is negative when interpreted as signed
+    pub fn is_negative(&self) -> bool {
+        BITS > 0 && self.bit(BITS - 1)
+    }
+
+    /// Arithmetic shift right, filling with MSB
+    pub fn ashr(&self, n: usize) -> Self {
+        let mut value = self >> n;
+        if self.is_negative() {
+            for i in BITS.saturating_sub(n)..BITS {
+                value.set_bit(i, true);
+            }
+        }
+        value
+    }
+
+    pub fn wrapping_add(&self, rhs: &Self) -> Self {
+        let mut value = Self::zero();
+        let mut carry = 0u64;
+        for (dst, (&a, &b)) in value
+            .words_mut()
+            .iter_mut()
+            .zip(self.as_words().iter().zip(rhs.as_words()))
+        {
+            let sum = a as u64 + b as u64 + carry;
+            *dst = sum as u32;
+            carry = sum >> 32;
+        }
+        value.canonicalize();
+        value
+    }
+
+    pub fn wrapping_sub(&self, rhs: &Self) -> Self {
+        self.wrapping_add(&rhs.wrapping_neg())
+    }
+
+    pub fn wrapping_neg(&self) -> Self {
+        (!self).wrapping_add(&Self::from(1u8))
+    }
+
+    pub fn wrapping_mul(&self, rhs: &Self) -> Self {
+        let mut value = Self::zero();
+        let (a, b) = (self.as_words(), rhs.as_words());
+        let dst = value.words_mut();
+        for i in 0..a.len() {
+            let mut carry = 0u64;
+            for j in 0..(dst.len() - i) {
+                let cur = dst[i + j] as u64 + a[i] as u64 * b[j] as u64 + carry;
+                dst[i + j] = cur as u32;
+                carry = cur >> 32;
+            }
+        }
+        value.canonicalize();
+        value
+    }
+
+    fn zip_words(&self, rhs: &Self, f: impl Fn(u32, u32) -> u32) -> Self {
+        let mut value = self.clone();
+        for (x, &y) in value.words_mut().iter_mut().zip(rhs.as_words()) {
+            *x = f(*x, y);
+        }
+        value
+    }
+
+    fn and(&self, rhs: &Self) -> Self {
+        self.zip_words(rhs, |x, y| x & y)
+    }
+
+    fn or(&self, rhs: &Self) -> Self {
+        self.zip_words(rhs, |x, y| x | y)
+    }
+
+    fn xor(&self, rhs: &Self) -> Self {
+        self.zip_words(rhs, |x, y| x ^ y)
+    }
+
+    /// Compare as two's complement signed values
+    pub fn cmp_signed(&self, other: &Self) -> Ordering {
+        match (self.is_negative(), other.is_negative()) {
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            _ => self.cmp(other),
+        }
+    }
+
+    /// Low 64 bits, higher bits are discarded
+    pub fn low_u64(&self) -> u64 {
+        let words = self.as_words();
+        (0..2).fold(0, |acc, i| {
+            acc | (words.get(i).copied().unwrap_or(0) as u64) << (32 * i)
+        })
+    }
+
+    /// Low 128 bits, higher bits are discarded
+    pub fn low_u128(&self) -> u128 {
+        let words = self.as_words();
+        (0..4).fold(0, |acc, i| {
+            acc | (words.get(i).copied().unwrap_or(0) as u128) << (32 * i)
+        })
+    }
+
+    /// Interpret as unsigned, `None` if the value does not fit
+    pub fn to_u128(&self) -> Option<u128> {
+        let high_zero = self.as_words().iter().skip(4).all(|&w| w == 0);
+        high_zero.then(|| self.low_u128())
+    }
+
+    /// Interpret as two's complement signed, `None` if the value does not fit
+    pub fn to_i128(&self) -> Option<i128> {
+        let neg = self.is_negative();
+        if BITS < 128 {
+            let value = self.low_u128();
+            let value = if neg { value | (!0 << BITS) } else { value };
+            return Some(value as i128);
+        }
+        // every bit from bit 127 up shall equal the sign bit
+        (127..BITS)
+            .all(|i| self.bit(i) == neg)
+            .then(|| self.low_u128() as i128)
+    }
+
+    /// Build from unsigned value, truncated to `BITS` bits
+    pub fn from_u128(value: u128) -> Self {
+        let mut result = Self::zero();
+        for (i, word) in result.words_mut().iter_mut().take(4).enumerate() {
+            *word = (value >> (32 * i)) as u32;
+        }
+        result.canonicalize();
+        result
+    }
+
+    /// Build from signed value, sign-extended or truncated to `BITS` bits
+    pub fn from_i128(value: i128) -> Self {
+        let mut result = Self::zero();
+        for (i, word) in result.words_mut().iter_mut().enumerate() {
+            *word = (value >> (32 * i).min(127)) as u32;
+        }
+        result.canonicalize();
+        result
+    }
+}
+
+impl<const BITS: usize> Default for BitVec<BITS> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<const BITS: usize> fmt::Debug for BitVec<BITS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BitVec<{BITS}>({self:#x})")
+    }
+}
+
+/// Unsigned comparison
+impl<const BITS: usize> Ord for BitVec<BITS> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_words()
+            .iter()
+            .rev()
+            .cmp(other.as_words().iter().rev())
+    }
+}
+
+impl<const BITS: usize> PartialOrd for BitVec<BITS> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+macro_rules! impl_from_uint {
+    ($($T: ty),*) => {
+        $(
+            /// Truncated to `BITS` bits
+            impl<const BITS: usize> From<$T> for BitVec<BITS> {
+                fn from(value: $T) -> Self {
+                    Self::from_u128(value as u128)
+                }
+            }
+
+            impl<const BITS: usize> TryFrom<&BitVec<BITS>> for $T {
+                type Error = OutOfRangeError;
+                fn try_from(value: &BitVec<BITS>) -> Result<Self, Self::Error> {
+                    let value = value.to_u128().ok_or(OutOfRangeError)?;
+                    <$T>::try_from(value).map_err(|_| OutOfRangeError)
+                }
+            }
+
+            impl<const BITS: usize> TryFrom<BitVec<BITS>> for $T {
+                type Error = OutOfRangeError;
+                fn try_from(value: BitVec<BITS>) -> Result<Self, Self::Error> {
+                    <$T>::try_from(&value)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_uint!(u8, u16, u32, u64, u128);
+
+impl<const BITS: usize> From<bool> for BitVec<BITS> {
+    fn from(value: bool) -> Self {
+        Self::from(value as u8)
+    }
+}
+
+impl<const BITS: usize> Not for &BitVec<BITS> {
+    type Output = BitVec<BITS>;
+    fn not(self) -> BitVec<BITS> {
+        let mut value = self.clone();
+        value.words_mut().iter_mut().for_each(|w| *w = !*w);
+        value.canonicalize();
+        value
+    }
+}
+
+impl<const BITS: usize> Not for BitVec<BITS> {
+    type Output = BitVec<BITS>;
+    fn not(self) -> BitVec<BITS> {
+        !&self
+    }
+}
+
+impl<const BITS: usize> Neg for &BitVec<BITS> {
+    type Output = BitVec<BITS>;
+    fn neg(self) -> BitVec<BITS> {
+        self.wrapping_neg()
+    }
+}
+
+impl<const BITS: usize> Neg for BitVec<BITS> {
+    type Output = BitVec<BITS>;
+    fn neg(self) -> BitVec<BITS> {
+        self.wrapping_neg()
+    }
+}
+
+impl_binary_op!(BitVec, BitAnd, bitand, BitAndAssign, bitand_assign, and);
+impl_binary_op!(BitVec, BitOr, bitor, BitOrAssign, bitor_assign, or);
+impl_binary_op!(BitVec, BitXor, bitxor, BitXorAssign, bitxor_assign, xor);
+impl_binary_op!(BitVec, Add, add, AddAssign, add_assign, wrapping_add);
+impl_binary_op!(BitVec, Sub, sub, SubAssign, sub_assign, wrapping_sub);
+impl_binary_op!(BitVec, Mul, mul, MulAssign, mul_assign, wrapping_mul);
+
+/// Logical shift left, shifting by `BITS` or more yields zero
+impl<const BITS: usize> Shl<usize> for &BitVec<BITS> {
+    type Output = BitVec<BITS>;
+    fn shl(self, n: usize) -> BitVec<BITS> {
+        let mut value = BitVec::zero();
+        if n < BITS {
+            for (i, word) in value.words_mut().iter_mut().enumerate() {
+                *word = bits::read_u32(self.as_words(), 32 * i as isize - n as isize);
+            }
+            value.canonicalize();
+        }
+        value
+    }
+}
+
+/// Logical shift right, shifting by `BITS` or more yields zero
+impl<const BITS: usize> Shr<usize> for &BitVec<BITS> {
+    type Output = BitVec<BITS>;
+    fn shr(self, n: usize) -> BitVec<BITS> {
+        let mut value = BitVec::zero();
+        if n < BITS {
+            for (i, word) in value.words_mut().iter_mut().enumerate() {
+                *word = bits::read_u32(self.as_words(), (32 * i + n) as isize);
+            }
+        }
+        value
+    }
+}
+
+impl<const BITS: usize> Shl<usize> for BitVec<BITS> {
+    type Output = BitVec<BITS>;
+    fn shl(self, n: usize) -> BitVec<BITS> {
+        &self << n
+    }
+}
+
+impl<const BITS: usize> Shr<usize> for BitVec<BITS> {
+    type Output = BitVec<BITS>;
+    fn shr(self, n: usize) -> BitVec<BITS> {
+        &self >> n
+    }
+}
+
+impl<const BITS: usize> ShlAssign<usize> for BitVec<BITS> {
+    fn shl_assign(&mut self, n: usize) {
+        *self = &*self << n;
+    }
+}
+
+impl<const BITS: usize> ShrAssign<usize> for BitVec<BITS> {
+    fn shr_assign(&mut self, n: usize) {
+        *self = &*self >> n;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_carries_across_words() {
+        let a = BitVec::<96>::from_words(&[!0, !0, 0]);
+        assert_eq!(
+            a.clone() + BitVec::from(1u8),
+            BitVec::from_words(&[0, 0, 1])
+        );
+        // wraps at `BITS`, not at the word boundary
+        let max = BitVec::<70>::ones();
+        assert!((max.clone() + BitVec::from(1u8)).is_zero());
+        assert_eq!(BitVec::<70>::zero() - BitVec::from(1u8), max);
+    }
+
+    #[test]
+    fn mul_wraps() {
+        let a = BitVec::<100>::from_u128(0x1_0000_0001);
+        assert_eq!(a.clone() * a, BitVec::from_u128(0x1_0000_0002_0000_0001));
+        let b = BitVec::<40>::from(0x80_0000_0001u64);
+        assert_eq!(b.clone() * b, BitVec::from(1u8));
+    }
+
+    #[test]
+    fn shifts() {
+        let one = BitVec::<200>::from(1u8);
+        assert_eq!((&one << 199).as_words()[6], 1 << 7);
+        assert!((&one << 200).is_zero());
+        assert_eq!(&(&one << 150) >> 150, one);
+        let x = BitVec::<72>::from_u128(0xab_cdef_0123_4567_89ab);
+        assert_eq!(&x << 36, BitVec::from_u128(0x34_5678_9ab0_0000_0000));
+        assert_eq!(&x >> 36, BitVec::from_u128(0xa_bcde_f012));
+        assert_eq!(x.ashr(68), BitVec::from_u128(0xff_ffff_ffff_ffff_fffa));
+        assert_eq!(x.ashr(72), BitVec::ones());
+    }
+
+    #[test]
+    fn resize_and_part() {
+        let x = BitVec::<12>::from(0x8f0u16);
+        assert_eq!(x.resize::<16>(), BitVec::from(0x08f0u16));
+        assert_eq!(x.resize_signed::<16>(), BitVec::from(0xf8f0u16));
+        assert_eq!(x.resize::<8>(), BitVec::from(0xf0u8));
+        assert_eq!(x.part::<4>(4), BitVec::from(0xfu8));
+        let mut y = BitVec::<40>::zero();
+        y.set_part(30, &BitVec::<4>::from(0b1011u8));
+        assert_eq!(y.low_u64(), 0b1011 << 30);
+    }
+
+    #[test]
+    fn i128_round_trip() {
+        for value in [0, 1, -1, i128::MIN, i128::MAX, -0x1234_5678_9abc] {
+            assert_eq!(BitVec::<128>::from_i128(value).to_i128(), Some(value));
+            let wide = BitVec::<200>::from_i128(value);
+            assert_eq!(wide.is_negative(), value < 0);
+            assert_eq!(wide.to_i128(), Some(value));
+        }
+        assert_eq!(BitVec::<200>::from_i128(-1), BitVec::ones());
+        assert_eq!(BitVec::<8>::from_i128(-2).to_i128(), Some(-2));
+        assert_eq!(BitVec::<8>::from_i128(-2).to_u128(), Some(0xfe));
+    }
+
+    #[test]
+    fn i128_out_of_range() {
+        let big = BitVec::<200>::from_u128(1 << 127);
+        assert_eq!(big.to_i128(), None);
+        assert_eq!(big.to_u128(), Some(1 << 127));
+        let huge = BitVec::<200>::from(1u8) << 128;
+        assert_eq!(huge.to_u128(), None);
+        assert_eq!(huge.to_i128(), None);
+        assert_eq!(u64::try_from(&huge), Err(OutOfRangeError));
+        assert_eq!((-huge).to_i128(), None);
+        assert_eq!(u8::try_from(BitVec::<200>::from(255u8)), Ok(255));
+    }
+
+    #[test]
+    fn unused_bits_stay_zero() {
+        assert_eq!(BitVec::<33>::ones().as_words(), [!0, 1]);
+        assert_eq!(BitVec::<33>::from_words(&[0, !0]).as_words(), [0, 1]);
+        assert_eq!(
+            BitVec::<12>::from_le_bytes(&[0xff, 0xff]).to_le_bytes(),
+            [0xff, 0x0f]
+        );
+        assert_eq!((!BitVec::<5>::zero()).count_ones(), 5);
+    }
+
+    #[test]
+    fn signed_compare() {
+        let neg = BitVec::<70>::from_i128(-5);
+        let pos = BitVec::<70>::from(3u8);
+        assert_eq!(neg.cmp_signed(&pos), Ordering::Less);
+        assert_eq!(neg.cmp(&pos), Ordering::Greater);
+        assert_eq!(neg.cmp_signed(&BitVec::from_i128(-4)), Ordering::Less);
+    }
+
+    #[test]
+    fn le_bytes_round_trip() {
+        let bytes: Vec<u8> = (1..=9).collect();
+        let v = BitVec::<70>::from_le_bytes(&bytes);
+        assert_eq!(v, BitVec::from_u128(0x09_0807_0605_0403_0201));
+        assert_eq!(v.to_le_bytes(), bytes);
+        let v = BitVec::<33>::from_le_bytes(&[0x78, 0x56, 0x34, 0x12, 0xff]);
+        assert_eq!(v.as_words(), [0x1234_5678, 1]);
+        assert_eq!(v.to_le_bytes(), [0x78, 0x56, 0x34, 0x12, 0x01]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_le_bytes_wrong_len() {
+        BitVec::<12>::from_le_bytes(&[0; 3]);
+    }
+}
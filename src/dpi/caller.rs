@@ -0,0 +1,171 @@
+use std::{any::Any, fmt, panic};
+
+use super::*;
+
+/// Source location of the SystemVerilog call of the current import
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallerInfo {
+    pub file: &'static CStr,
+    pub line: u32,
+}
+
+/// `file:line`
+impl fmt::Display for CallerInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file.to_string_lossy(), self.line)
+    }
+}
+
+/// Get the caller of the current import, `None` if not called from a `context` import.
+///
+/// See also [`sys::svGetCallerInfo`]
+pub fn caller_info() -> Option<CallerInfo> {
+    let mut file = ptr::null();
+    let mut line = 0;
+    unsafe {
+        if sys::svGetCallerInfo(&mut file, &mut line) == 0 || file.is_null() {
+            return None;
+        }
+        Some(CallerInfo {
+            file: CStr::from_ptr(file),
+            line: line as u32,
+        })
+    }
+}
+
+/// Where the current import is called from, for diagnostics
+#[derive(Debug, Clone, Copy)]
+pub struct CallSite {
+    pub scope: Option<SvScope>,
+    pub caller: Option<CallerInfo>,
+}
+
+impl CallSite {
+    /// See also [`SvScope::get_current`] and [`caller_info`]
+    pub fn current() -> Self {
+        CallSite {
+            scope: SvScope::get_current(),
+            caller: caller_info(),
+        }
+    }
+}
+
+/// `file:line (scope)`, missing parts are omitted
+impl fmt::Display for CallSite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.caller, self.scope) {
+            (Some(caller), Some(scope)) => {
+                write!(f, "{caller} ({})", scope.name().to_string_lossy())
+            }
+            (Some(caller), None) => write!(f, "{caller}"),
+            (None, Some(scope)) => write!(f, "{}", scope.name().to_string_lossy()),
+            (None, None) => f.write_str("unknown call site"),
+        }
+    }
+}
+
+/// Print `msg` to stderr, prefixed with the [`CallSite`] of the current import
+pub fn report_error(msg: impl fmt::Display) {
+    eprintln!("error: {}: {msg}", CallSite::current());
+}
+
+/// Install a panic hook, which reports the [`CallSite`] of the current import
+/// before the previously installed hook prints the panic message.
+pub fn install_panic_hook() {
+    let prev = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        eprintln!("panic in DPI call from {}", CallSite::current());
+        prev(info);
+    }));
+}
+
+/// Run the body of an import, so that a panic never unwinds into the simulator.
+///
+/// The current scope is restored even if `f` panics. On panic, the message is reported with
+/// the simulation time and [`CallSite`], the simulation is terminated and `None` is returned.
+/// The import shall then return promptly, with any value.
+///
+/// With `vpi` feature, the simulation is finished by `vpi_control(vpiFinish, 1)`, where `1`
+/// is the diagnostic level, and the process exits with status `1` from the end of simulation
+/// callback. Any shutdown step of the simulator after its end of simulation callbacks, e.g.
+/// its summary, is skipped.
+///
+/// Without `vpi`, the simulator cannot be asked to finish, the process exits with status `1`
+/// at once. The simulator is not shut down, e.g. waveforms may not be flushed, enable `vpi`
+/// or handle the panic by [`std::panic::catch_unwind`] to finish cleanly.
+///
+/// See also `#[dpi_import(catch_panic)]`.
+pub fn catch_panic<R>(f: impl FnOnce() -> R) -> Option<R> {
+    let ret = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        let _guard = SvScope::get_current().map(SvScope::enter);
+        f()
+    }));
+    match ret {
+        Ok(ret) => Some(ret),
+        Err(payload) => {
+            report_panic(&*payload);
+            finish_simulation();
+            None
+        }
+    }
+}
+
+fn report_panic(payload: &(dyn Any + Send)) {
+    let msg = match (
+        payload.downcast_ref::<&str>(),
+        payload.downcast_ref::<String>(),
+    ) {
+        (Some(msg), _) => msg,
+        (_, Some(msg)) => msg.as_str(),
+        _ => "Box<dyn Any>",
+    };
+    let site = CallSite::current();
+    // the report shall not panic again, e.g. if the time is not available
+    #[cfg(any(feature = "sv2023", feature = "vpi"))]
+    match panic::catch_unwind(crate::get_sim_time) {
+        Ok(time) => eprintln!("error: {site} at {time}: panic: {msg}"),
+        Err(_) => eprintln!("error: {site}: panic: {msg}"),
+    }
+    #[cfg(not(any(feature = "sv2023", feature = "vpi")))]
+    eprintln!("error: {site}: panic: {msg}");
+}
+
+#[cfg(feature = "vpi")]
+fn finish_simulation() {
+    use crate::sys::vpi as sys;
+    use std::sync::Once;
+
+    // `cbEndOfSimulation` of `vpi_user.h`
+    const CB_END_OF_SIMULATION: i32 = 12;
+
+    unsafe extern "C" fn exit_failure(_: *mut sys::t_cb_data) -> i32 {
+        std::process::exit(1)
+    }
+
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| {
+        let mut cb_data = sys::t_cb_data {
+            reason: CB_END_OF_SIMULATION,
+            cb_rtn: Some(exit_failure),
+            obj: ptr::null_mut(),
+            time: ptr::null_mut(),
+            value: ptr::null_mut(),
+            index: 0,
+            user_data: ptr::null_mut(),
+        };
+        unsafe {
+            let handle = sys::vpi_register_cb(&mut cb_data);
+            if !handle.is_null() {
+                sys::vpi_release_handle(handle);
+            }
+        }
+    });
+    unsafe {
+        sys::vpi_control(sys::vpiFinish, 1);
+    }
+}
+
+#[cfg(not(feature = "vpi"))]
+fn finish_simulation() {
+    std::process::exit(1);
+}
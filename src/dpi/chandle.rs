@@ -0,0 +1,245 @@
+use std::{fmt, marker::PhantomData};
+
+use super::param::SvTransparent;
+use super::*;
+
+/// A `chandle` owning a Rust object, e.g. a model held by SystemVerilog between calls
+///
+/// The handle is a plain copyable value, ownership is managed explicitly: create it by
+/// [`Chandle::new`] and release it by [`Chandle::free`], usually in an import called at the
+/// end of the object's lifetime:
+///
+/// ```ignore
+/// #[svdpi::dpi_import]
+/// fn model_new() -> Chandle<Model> {
+///     Chandle::new(Box::new(Model::default()))
+/// }
+///
+/// #[svdpi::dpi_import]
+/// fn model_free(model: Chandle<Model>) {
+///     unsafe { model.free() }
+/// }
+/// ```
+///
+/// With `debug-chandle` feature, handles are tagged with the type and a generation, which
+/// are checked on every access, so that use-after-free, double free and handles of another
+/// type panic instead of being undefined behaviour. The handle is then an opaque token.
+#[repr(transparent)]
+pub struct Chandle<T> {
+    ptr: *mut c_void,
+    phantom: PhantomData<*mut T>,
+}
+
+impl<T: 'static> Chandle<T> {
+    /// Move `value` to the heap, owned by the returned handle
+    pub fn new(value: Box<T>) -> Self {
+        let ptr = Box::into_raw(value);
+        Chandle {
+            ptr: tag::register(ptr),
+            phantom: PhantomData,
+        }
+    }
+
+    /// The `null` handle, which owns nothing
+    pub const fn null() -> Self {
+        Chandle {
+            ptr: ptr::null_mut(),
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn is_null(self) -> bool {
+        self.ptr.is_null()
+    }
+
+    /// The value as passed to the simulator
+    pub fn as_raw(self) -> *mut c_void {
+        self.ptr
+    }
+
+    /// # Safety
+    ///
+    /// The handle shall be created by [`Chandle::new`] and not yet freed. No mutable reference
+    /// from [`Self::borrow_mut`] shall be alive at the same time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the handle is `null`, or with `debug-chandle` feature, if it is not a live
+    /// handle of `T`
+    pub unsafe fn borrow<'a>(self) -> &'a T {
+        unsafe { &*self.get() }
+    }
+
+    /// # Safety
+    ///
+    /// Same as [`Self::borrow`], and the reference shall be the only one to the value
+    ///
+    /// # Panics
+    ///
+    /// Same as [`Self::borrow`]
+    pub unsafe fn borrow_mut<'a>(self) -> &'a mut T {
+        unsafe { &mut *self.get() }
+    }
+
+    /// Take back the ownership, the handle is freed
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Self::borrow_mut`], and the handle shall not be used afterwards
+    ///
+    /// # Panics
+    ///
+    /// Same as [`Self::borrow`]
+    pub unsafe fn into_box(self) -> Box<T> {
+        assert!(!self.is_null(), "null chandle");
+        unsafe { Box::from_raw(tag::release(self.ptr)) }
+    }
+
+    /// Drop the owned value, `null` is ignored like `free` in C
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Self::into_box`]
+    pub unsafe fn free(self) {
+        if !self.is_null() {
+            drop(unsafe { self.into_box() });
+        }
+    }
+
+    fn get(self) -> *mut T {
+        assert!(!self.is_null(), "null chandle");
+        tag::lookup(self.ptr)
+    }
+}
+
+impl<T> Clone for Chandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Chandle<T> {}
+
+impl<T> PartialEq for Chandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr == other.ptr
+    }
+}
+
+impl<T> Eq for Chandle<T> {}
+
+impl<T> fmt::Debug for Chandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Chandle").field(&self.ptr).finish()
+    }
+}
+
+// Safety : `Chandle<T>` is `#[repr(transparent)]` over `*mut c_void`, any value is a handle
+unsafe impl<T: 'static> SvTransparent for Chandle<T> {
+    type Repr = *mut c_void;
+
+    fn from_repr(repr: *mut c_void) -> Self {
+        Chandle {
+            ptr: repr,
+            phantom: PhantomData,
+        }
+    }
+
+    fn to_repr(self) -> *mut c_void {
+        self.ptr
+    }
+}
+
+crate::__impl_export_arg_basic!([T: 'static] Chandle<T>);
+
+#[cfg(not(feature = "debug-chandle"))]
+mod tag {
+    use std::ffi::c_void;
+
+    pub fn register<T>(ptr: *mut T) -> *mut c_void {
+        ptr as *mut c_void
+    }
+
+    pub fn lookup<T>(handle: *mut c_void) -> *mut T {
+        handle as *mut T
+    }
+
+    pub fn release<T>(handle: *mut c_void) -> *mut T {
+        handle as *mut T
+    }
+}
+
+// handles are tokens of a generation counter, never reused, mapped to live objects
+#[cfg(feature = "debug-chandle")]
+mod tag {
+    use std::{
+        any::{TypeId, type_name},
+        collections::BTreeMap,
+        ffi::c_void,
+        sync::{Mutex, MutexGuard, PoisonError},
+    };
+
+    struct Live {
+        type_id: TypeId,
+        type_name: &'static str,
+        ptr: usize,
+    }
+
+    struct Registry {
+        generation: usize,
+        live: BTreeMap<usize, Live>,
+    }
+
+    static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+        generation: 0,
+        live: BTreeMap::new(),
+    });
+
+    // a failed check panics with the lock held, the registry itself stays consistent
+    fn lock() -> MutexGuard<'static, Registry> {
+        REGISTRY.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn register<T: 'static>(ptr: *mut T) -> *mut c_void {
+        let mut registry = lock();
+        registry.generation += 1;
+        let handle = registry.generation;
+        registry.live.insert(
+            handle,
+            Live {
+                type_id: TypeId::of::<T>(),
+                type_name: type_name::<T>(),
+                ptr: ptr as usize,
+            },
+        );
+        handle as *mut c_void
+    }
+
+    pub fn lookup<T: 'static>(handle: *mut c_void) -> *mut T {
+        let registry = lock();
+        checked::<T>(registry.live.get(&(handle as usize)), handle)
+    }
+
+    pub fn release<T: 'static>(handle: *mut c_void) -> *mut T {
+        let mut registry = lock();
+        let ptr = checked::<T>(registry.live.get(&(handle as usize)), handle);
+        registry.live.remove(&(handle as usize));
+        ptr
+    }
+
+    fn checked<T: 'static>(live: Option<&Live>, handle: *mut c_void) -> *mut T {
+        let Some(live) = live else {
+            panic!(
+                "chandle {handle:?} of `{}` is freed or invalid",
+                type_name::<T>()
+            );
+        };
+        assert!(
+            live.type_id == TypeId::of::<T>(),
+            "chandle {handle:?} of `{}` used as `{}`",
+            live.type_name,
+            type_name::<T>()
+        );
+        live.ptr as *mut T
+    }
+}
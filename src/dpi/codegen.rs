@@ -0,0 +1,181 @@
+use std::{fmt::Write, fs, io, path::Path};
+
+use super::import::{Direction, DpiParam, DpiSignature, SvType, registered_signatures};
+
+/// A SystemVerilog package of `import "DPI-C"` declarations, and the matching C header
+///
+/// Signatures come from `#[dpi_import]` functions, either every import linked into the
+/// program with [`SvPackage::registered`], or listed by hand via `dpi_signature!`. Since a
+/// build script cannot see the crate it builds, generate the package in a small binary or
+/// test of the DPI library itself.
+#[derive(Debug, Clone)]
+pub struct SvPackage {
+    name: String,
+    functions: Vec<DpiSignature>,
+}
+
+impl SvPackage {
+    pub fn new(name: &str) -> Self {
+        SvPackage {
+            name: name.to_owned(),
+            functions: Vec::new(),
+        }
+    }
+
+    /// Every import linked into the program, sorted by name. See [`registered_signatures`]
+    pub fn registered(name: &str) -> Self {
+        let mut functions: Vec<_> = registered_signatures().copied().collect();
+        functions.sort_by_key(|f| f.name);
+        SvPackage {
+            name: name.to_owned(),
+            functions,
+        }
+    }
+
+    pub fn function(mut self, signature: &DpiSignature) -> Self {
+        self.functions.push(*signature);
+        self
+    }
+
+    pub fn functions<'a>(mut self, signatures: impl IntoIterator<Item = &'a DpiSignature>) -> Self {
+        self.functions.extend(signatures.into_iter().copied());
+        self
+    }
+
+    /// `package <name>; ... endpackage`
+    pub fn to_sv(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "// Generated by svdpi, do not edit").unwrap();
+        writeln!(out, "package {};", self.name).unwrap();
+        for f in &self.functions {
+            let context = if f.context { "context " } else { "" };
+            let kind = match (f.task, f.sv_ret) {
+                (true, _) => "task".to_owned(),
+                (false, Some(ty)) => format!("function {}", sv_type(ty)),
+                (false, None) => "function void".to_owned(),
+            };
+            let params: Vec<_> = f.params.iter().map(sv_param).collect();
+            writeln!(
+                out,
+                "  import \"DPI-C\" {context}{kind} {}({});",
+                f.name,
+                params.join(", ")
+            )
+            .unwrap();
+        }
+        writeln!(out, "endpackage").unwrap();
+        out
+    }
+
+    /// C prototypes using `svdpi.h` types, guarded by `<NAME>_H`
+    ///
+    /// # Panics
+    ///
+    /// Panics if a function returns a type that is not a valid DPI result, e.g. a hand-built
+    /// [`DpiSignature`] returning `logic` vectors or open arrays
+    pub fn to_c_header(&self) -> String {
+        let guard = format!("{}_H", self.name.to_uppercase());
+        let mut out = String::new();
+        writeln!(out, "/* Generated by svdpi, do not edit */").unwrap();
+        writeln!(out, "#ifndef {guard}\n#define {guard}\n").unwrap();
+        writeln!(out, "#include \"svdpi.h\"\n").unwrap();
+        writeln!(out, "#ifdef __cplusplus\nextern \"C\" {{\n#endif\n").unwrap();
+        for f in &self.functions {
+            let ret = match f.sv_ret {
+                _ if f.task => "int",
+                Some(SvType::Basic { c, .. }) => c,
+                Some(SvType::String) => "const char*",
+                Some(SvType::BitVec(_)) => "svBitVecVal",
+                Some(ty) => panic!(
+                    "function `{}` returns {ty:?}, which is not a valid DPI result",
+                    f.name
+                ),
+                None => "void",
+            };
+            let params: Vec<_> = f.params.iter().map(c_param).collect();
+            let params = match params.is_empty() {
+                true => "void".to_owned(),
+                false => params.join(", "),
+            };
+            writeln!(out, "{ret} {}({params});", f.name).unwrap();
+        }
+        writeln!(out, "\n#ifdef __cplusplus\n}}\n#endif\n\n#endif").unwrap();
+        out
+    }
+
+    /// Write [`SvPackage::to_sv`], the file is left untouched if unchanged
+    pub fn write_sv(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        write_if_changed(path.as_ref(), &self.to_sv())
+    }
+
+    /// Write [`SvPackage::to_c_header`], the file is left untouched if unchanged
+    ///
+    /// # Panics
+    ///
+    /// Same as [`SvPackage::to_c_header`]
+    pub fn write_c_header(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        write_if_changed(path.as_ref(), &self.to_c_header())
+    }
+}
+
+// avoid touching the file, so that simulators and build scripts do not rebuild needlessly
+fn write_if_changed(path: &Path, content: &str) -> io::Result<()> {
+    if fs::read_to_string(path).is_ok_and(|old| old == content) {
+        return Ok(());
+    }
+    fs::write(path, content)
+}
+
+// packed dimension `[BITS-1:0]`
+fn range(bits: usize) -> String {
+    assert!(bits > 0, "packed vector width shall be positive");
+    format!("[{}:0]", bits - 1)
+}
+
+fn sv_type(ty: SvType) -> String {
+    match ty {
+        SvType::Basic { sv, .. } | SvType::OpenArray { sv } => sv.to_owned(),
+        SvType::String => "string".to_owned(),
+        SvType::BitVec(bits) | SvType::BitVecOpenArray(bits) => format!("bit {}", range(bits)),
+        SvType::LogicVec(bits) | SvType::LogicVecOpenArray(bits) => {
+            format!("logic {}", range(bits))
+        }
+    }
+}
+
+fn sv_param(param: &DpiParam) -> String {
+    let direction = match param.direction {
+        Direction::Input => "input",
+        Direction::Output => "output",
+        Direction::Inout => "inout",
+    };
+    let dims = if param.sv_type.is_open_array() {
+        "[]"
+    } else {
+        ""
+    };
+    format!(
+        "{direction} {} {}{dims}",
+        sv_type(param.sv_type),
+        param.name
+    )
+}
+
+// input arguments are passed by value or `const` pointer, others by pointer
+fn c_param(param: &DpiParam) -> String {
+    let input = param.direction == Direction::Input;
+    let ty = match (param.sv_type, input) {
+        (SvType::Basic { c, .. }, true) => c.to_owned(),
+        (SvType::Basic { c, .. }, false) => format!("{c}*"),
+        (SvType::String, true) => "const char*".to_owned(),
+        (SvType::String, false) => "const char**".to_owned(),
+        (SvType::BitVec(_), true) => "const svBitVecVal*".to_owned(),
+        (SvType::BitVec(_), false) => "svBitVecVal*".to_owned(),
+        (SvType::LogicVec(_), true) => "const svLogicVecVal*".to_owned(),
+        (SvType::LogicVec(_), false) => "svLogicVecVal*".to_owned(),
+        // open arrays
+        (_, true) => "const svOpenArrayHandle".to_owned(),
+        (_, false) => "svOpenArrayHandle".to_owned(),
+    };
+    format!("{ty} {}", param.name)
+}
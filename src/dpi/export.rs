@@ -0,0 +1,344 @@
+use std::{error::Error, fmt};
+
+use super::param::SvBasicType;
+use super::*;
+
+/// The calling import task was disabled while an exported task was running.
+///
+/// The import task shall return `1` to the simulator, see LRM 2023 35.9.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Disabled;
+
+impl fmt::Display for Disabled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("exported task was disabled")
+    }
+}
+
+impl Error for Disabled {}
+
+impl Disabled {
+    /// Whether the current import is in the disabled state. See also [`sys::svIsDisabledState`]
+    pub fn is_disabled() -> bool {
+        unsafe { sys::svIsDisabledState() != 0 }
+    }
+
+    /// `Err(Disabled)` if the current import is in the disabled state, e.g. after calling
+    /// an exported function
+    pub fn check() -> Result<(), Disabled> {
+        match Self::is_disabled() {
+            false => Ok(()),
+            true => Err(Disabled),
+        }
+    }
+
+    /// Acknowledge the disable. An import function shall call this before returning due to
+    /// a disable, while an import task returns `1` instead. See also [`sys::svAckDisabledState`]
+    pub fn acknowledge(self) {
+        unsafe { sys::svAckDisabledState() }
+    }
+}
+
+/// Rust types passed to an exported SystemVerilog function or task, see [`dpi_export_decl!`]
+///
+/// Values are converted into a buffer kept alive during the call. `&mut T` arguments are
+/// `output` or `inout` arguments, the buffer is copied back after the call.
+///
+/// [`dpi_export_decl!`]: crate::dpi_export_decl
+pub trait ExportArg: Sized {
+    /// Argument type of the `extern "C"` declaration
+    type Abi;
+    type Buf;
+
+    fn to_buf(&self) -> Self::Buf;
+
+    fn as_abi(buf: &mut Self::Buf) -> Self::Abi;
+
+    fn write_back(self, _buf: Self::Buf) {}
+}
+
+/// Rust types returned from an exported SystemVerilog function, see [`dpi_export_decl!`]
+///
+/// [`dpi_export_decl!`]: crate::dpi_export_decl
+pub trait ExportRet {
+    /// Return type of the `extern "C"` declaration
+    type Abi;
+
+    /// # Safety
+    ///
+    /// `abi` shall be the value returned by the exported function
+    unsafe fn from_abi(abi: Self::Abi) -> Self;
+}
+
+// a blanket impl over `SvBasicType` would overlap with the `&mut T` impls,
+// so `#[derive(SvEnum)]` and `#[derive(SvNewtype)]` also expand to this
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_export_arg_basic {
+    ($([$($generics: tt)*] $T: ty),* $(,)?) => {
+        $(
+            impl<$($generics)*> $crate::dpi::export::ExportArg for $T {
+                type Abi = <$T as $crate::dpi::param::SvBasicType>::Underlying;
+                type Buf = <$T as $crate::dpi::param::SvBasicType>::Underlying;
+
+                fn to_buf(&self) -> Self::Buf {
+                    $crate::dpi::param::SvBasicType::to_underlying(*self)
+                }
+
+                fn as_abi(buf: &mut Self::Buf) -> Self::Abi {
+                    *buf
+                }
+            }
+
+            impl<$($generics)*> $crate::dpi::export::ExportArg for &mut $T {
+                type Abi = *mut <$T as $crate::dpi::param::SvBasicType>::Underlying;
+                type Buf = <$T as $crate::dpi::param::SvBasicType>::Underlying;
+
+                fn to_buf(&self) -> Self::Buf {
+                    $crate::dpi::param::SvBasicType::to_underlying(**self)
+                }
+
+                fn as_abi(buf: &mut Self::Buf) -> Self::Abi {
+                    buf
+                }
+
+                fn write_back(self, buf: Self::Buf) {
+                    *self = <$T as $crate::dpi::param::SvBasicType>::from_underlying(buf);
+                }
+            }
+        )*
+    };
+}
+
+crate::__impl_export_arg_basic!(
+    [] i8,
+    [] u8,
+    [] i16,
+    [] u16,
+    [] i32,
+    [] u32,
+    [] i64,
+    [] u64,
+    [] f64,
+    [] f32,
+    [] bool,
+    [] Logic,
+    [T] *mut T,
+);
+
+impl ExportArg for &CStr {
+    type Abi = *const c_char;
+    type Buf = *const c_char;
+
+    fn to_buf(&self) -> *const c_char {
+        self.as_ptr()
+    }
+
+    fn as_abi(buf: &mut *const c_char) -> *const c_char {
+        *buf
+    }
+}
+
+/// # Panics
+///
+/// Panics if the string contains an interior nul byte.
+impl ExportArg for &str {
+    type Abi = *const c_char;
+    type Buf = CString;
+
+    fn to_buf(&self) -> CString {
+        CString::new(*self).unwrap()
+    }
+
+    fn as_abi(buf: &mut CString) -> *const c_char {
+        buf.as_ptr()
+    }
+}
+
+impl<const BITS: usize> ExportArg for &BitVec<BITS> {
+    type Abi = *const sys::svBitVecVal;
+    type Buf = *const sys::svBitVecVal;
+
+    fn to_buf(&self) -> *const sys::svBitVecVal {
+        self.as_words().as_ptr()
+    }
+
+    fn as_abi(buf: &mut *const sys::svBitVecVal) -> *const sys::svBitVecVal {
+        *buf
+    }
+}
+
+impl<const BITS: usize> ExportArg for &mut BitVec<BITS> {
+    type Abi = *mut sys::svBitVecVal;
+    type Buf = Vec<sys::svBitVecVal>;
+
+    fn to_buf(&self) -> Vec<sys::svBitVecVal> {
+        self.as_words().to_vec()
+    }
+
+    fn as_abi(buf: &mut Vec<sys::svBitVecVal>) -> *mut sys::svBitVecVal {
+        buf.as_mut_ptr()
+    }
+
+    fn write_back(self, buf: Vec<sys::svBitVecVal>) {
+        *self = BitVec::from_words(&buf);
+    }
+}
+
+fn lv_words<const BITS: usize>(value: &LogicVec<BITS>) -> Vec<sys::svLogicVecVal> {
+    let (aval, bval) = (value.aval().as_words(), value.bval().as_words());
+    let words = aval.iter().zip(bval);
+    words
+        .map(|(&aval, &bval)| sys::svLogicVecVal { aval, bval })
+        .collect()
+}
+
+impl<const BITS: usize> ExportArg for &LogicVec<BITS> {
+    type Abi = *const sys::svLogicVecVal;
+    type Buf = Vec<sys::svLogicVecVal>;
+
+    fn to_buf(&self) -> Vec<sys::svLogicVecVal> {
+        lv_words(self)
+    }
+
+    fn as_abi(buf: &mut Vec<sys::svLogicVecVal>) -> *const sys::svLogicVecVal {
+        buf.as_ptr()
+    }
+}
+
+impl<const BITS: usize> ExportArg for &mut LogicVec<BITS> {
+    type Abi = *mut sys::svLogicVecVal;
+    type Buf = Vec<sys::svLogicVecVal>;
+
+    fn to_buf(&self) -> Vec<sys::svLogicVecVal> {
+        lv_words(self)
+    }
+
+    fn as_abi(buf: &mut Vec<sys::svLogicVecVal>) -> *mut sys::svLogicVecVal {
+        buf.as_mut_ptr()
+    }
+
+    fn write_back(self, buf: Vec<sys::svLogicVecVal>) {
+        let aval: Vec<_> = buf.iter().map(|w| w.aval).collect();
+        let bval: Vec<_> = buf.iter().map(|w| w.bval).collect();
+        *self = LogicVec::from_planes(BitVec::from_words(&aval), BitVec::from_words(&bval));
+    }
+}
+
+impl ExportRet for () {
+    type Abi = ();
+
+    unsafe fn from_abi(_abi: ()) {}
+}
+
+impl<T: SvBasicType> ExportRet for T {
+    type Abi = T::Underlying;
+
+    unsafe fn from_abi(abi: T::Underlying) -> Self {
+        T::from_underlying(abi)
+    }
+}
+
+/// The string returned by the simulator is only valid until the next call, so it is copied
+impl ExportRet for CString {
+    type Abi = *const c_char;
+
+    unsafe fn from_abi(abi: *const c_char) -> Self {
+        unsafe { CStr::from_ptr(abi) }.to_owned()
+    }
+}
+
+/// Declare SystemVerilog `export "DPI-C"` functions and tasks, and call them from Rust.
+///
+/// Each declaration becomes a Rust function taking the [`SvScope`] the export is called in,
+/// followed by the declared arguments. Arguments are [`ExportArg`], `&mut T` for `output` and
+/// `inout` arguments. A `fn` returns its [`ExportRet`] result, while a `task` returns
+/// `Result<(), Disabled>`, and `Err` if the calling import was disabled.
+///
+/// An import task propagates the error by returning `Result<(), Disabled>` from its
+/// `#[dpi_import]` function, which returns `1` to the simulator as LRM 2023 35.9 requires.
+///
+/// ```ignore
+/// svdpi::dpi_export_decl! {
+///     /// `export "DPI-C" function sv_add;`
+///     pub fn sv_add(a: u32, b: u32) -> u32;
+///     /// `export "DPI-C" task sv_wait;`
+///     pub task sv_wait(cycles: u32);
+/// }
+///
+/// let sum = sv_add(scope, 1, 2);
+/// sv_wait(scope, 10)?;
+/// ```
+#[macro_export]
+macro_rules! dpi_export_decl {
+    () => {};
+    (
+        $(#[$meta: meta])*
+        $vis: vis fn $name: ident($($arg: ident: $ty: ty),* $(,)?) -> $ret: ty;
+        $($rest: tt)*
+    ) => {
+        $(#[$meta])*
+        $vis fn $name(scope: $crate::dpi::SvScope, $($arg: $ty),*) -> $ret {
+            unsafe extern "C" {
+                fn $name(
+                    $($arg: <$ty as $crate::dpi::export::ExportArg>::Abi),*
+                ) -> <$ret as $crate::dpi::export::ExportRet>::Abi;
+            }
+
+            $(
+                #[allow(unused_mut)]
+                let mut $arg = (<$ty as $crate::dpi::export::ExportArg>::to_buf(&$arg), $arg);
+            )*
+            let ret = scope.with_current(|| unsafe {
+                $name($(<$ty as $crate::dpi::export::ExportArg>::as_abi(&mut $arg.0)),*)
+            });
+            $(<$ty as $crate::dpi::export::ExportArg>::write_back($arg.1, $arg.0);)*
+            unsafe { <$ret as $crate::dpi::export::ExportRet>::from_abi(ret) }
+        }
+
+        $crate::dpi_export_decl!($($rest)*);
+    };
+    (
+        $(#[$meta: meta])*
+        $vis: vis fn $name: ident($($arg: ident: $ty: ty),* $(,)?);
+        $($rest: tt)*
+    ) => {
+        $crate::dpi_export_decl! {
+            $(#[$meta])*
+            $vis fn $name($($arg: $ty),*) -> ();
+            $($rest)*
+        }
+    };
+    (
+        $(#[$meta: meta])*
+        $vis: vis task $name: ident($($arg: ident: $ty: ty),* $(,)?);
+        $($rest: tt)*
+    ) => {
+        $(#[$meta])*
+        $vis fn $name(
+            scope: $crate::dpi::SvScope,
+            $($arg: $ty),*
+        ) -> ::std::result::Result<(), $crate::dpi::export::Disabled> {
+            unsafe extern "C" {
+                fn $name(
+                    $($arg: <$ty as $crate::dpi::export::ExportArg>::Abi),*
+                ) -> ::std::ffi::c_int;
+            }
+
+            $(
+                #[allow(unused_mut)]
+                let mut $arg = (<$ty as $crate::dpi::export::ExportArg>::to_buf(&$arg), $arg);
+            )*
+            let ret = scope.with_current(|| unsafe {
+                $name($(<$ty as $crate::dpi::export::ExportArg>::as_abi(&mut $arg.0)),*)
+            });
+            $(<$ty as $crate::dpi::export::ExportArg>::write_back($arg.1, $arg.0);)*
+            match ret {
+                0 => $crate::dpi::export::Disabled::check(),
+                _ => Err($crate::dpi::export::Disabled),
+            }
+        }
+
+        $crate::dpi_export_decl!($($rest)*);
+    };
+}
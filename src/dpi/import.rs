@@ -0,0 +1,534 @@
+use std::{
+    ffi::{self, CString},
+    mem,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use super::export::Disabled;
+use super::param::*;
+use super::*;
+
+#[cfg(feature = "macros")]
+pub use svdpi_macros::{dpi_import, dpi_signature};
+
+/// Direction of an argument of a DPI import function
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Direction {
+    Input,
+    Output,
+    Inout,
+}
+
+/// SystemVerilog type of an argument or result, see LRM 2023 Table H.1
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SvType {
+    /// A [`SvBasicType`], e.g. `int unsigned` passed as `unsigned int`
+    Basic { sv: &'static str, c: &'static str },
+    /// `string`
+    String,
+    /// `bit [BITS-1:0]`
+    BitVec(usize),
+    /// `logic [BITS-1:0]`
+    LogicVec(usize),
+    /// Unsized unpacked array of a [`SvBasicType`], e.g. `int unsigned a[]`
+    OpenArray { sv: &'static str },
+    /// `bit [BITS-1:0] a[]`
+    BitVecOpenArray(usize),
+    /// `logic [BITS-1:0] a[]`
+    LogicVecOpenArray(usize),
+}
+
+impl SvType {
+    pub const fn basic<T: SvBasicType>() -> Self {
+        SvType::Basic {
+            sv: T::SV_NAME,
+            c: T::C_NAME,
+        }
+    }
+
+    pub const fn open_array<T: SvBasicType>() -> Self {
+        SvType::OpenArray { sv: T::SV_NAME }
+    }
+
+    /// Whether passed as `svOpenArrayHandle`
+    pub const fn is_open_array(self) -> bool {
+        matches!(
+            self,
+            SvType::OpenArray { .. } | SvType::BitVecOpenArray(_) | SvType::LogicVecOpenArray(_)
+        )
+    }
+}
+
+// a zero-width vector has no SystemVerilog type, rejected when the signature is built
+const fn packed_width(bits: usize) -> usize {
+    assert!(bits > 0, "packed vector width shall be positive");
+    bits
+}
+
+// packed `bit` results are passed as a single `svBitVecVal`, see LRM 2023 35.5.5
+const fn packed_ret_width(bits: usize) -> usize {
+    assert!(
+        bits <= 32,
+        "packed `bit` results shall be at most 32 bits wide"
+    );
+    packed_width(bits)
+}
+
+/// An argument recorded in [`DpiSignature`]
+#[derive(Clone, Copy, Debug)]
+pub struct DpiParam {
+    pub name: &'static str,
+    pub direction: Direction,
+    /// Rust type as written, `&mut` stripped for `output` and `inout` arguments
+    pub ty: &'static str,
+    pub sv_type: SvType,
+}
+
+/// Signature of a function annotated with `#[dpi_import]`, get it with `dpi_signature!`
+#[derive(Clone, Copy, Debug)]
+pub struct DpiSignature {
+    /// C symbol name, i.e. the name used in `import "DPI-C"`
+    pub name: &'static str,
+    /// Whether declared with `#[dpi_import(context)]`
+    pub context: bool,
+    pub params: &'static [DpiParam],
+    /// Rust return type as written, `None` for `void` functions
+    pub ret: Option<&'static str>,
+    /// `None` for `void` functions and tasks
+    pub sv_ret: Option<SvType>,
+    /// Whether the import is a `task`, see [`DpiRet::IS_TASK`]
+    pub task: bool,
+}
+
+// head of the intrusive list of registrations, pushed by constructors at load time
+static REGISTERED: AtomicPtr<Registration> = AtomicPtr::new(ptr::null_mut());
+
+/// A node of the list read by [`registered_signatures`], one `static` per `#[dpi_import]`
+#[doc(hidden)]
+pub struct Registration {
+    signature: &'static DpiSignature,
+    next: AtomicPtr<Registration>,
+}
+
+impl Registration {
+    pub const fn new(signature: &'static DpiSignature) -> Self {
+        Registration {
+            signature,
+            next: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+}
+
+/// Called once per registration by the constructor generated by `#[dpi_import]`
+#[doc(hidden)]
+pub fn register(registration: &'static Registration) {
+    let node = registration as *const Registration as *mut Registration;
+    let mut head = REGISTERED.load(Ordering::Relaxed);
+    loop {
+        registration.next.store(head, Ordering::Relaxed);
+        match REGISTERED.compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed) {
+            Ok(_) => return,
+            Err(current) => head = current,
+        }
+    }
+}
+
+/// Signatures of every `#[dpi_import]` function linked into the program, in no particular order
+///
+/// Registered by a static constructor when the program or library is loaded, on Linux,
+/// Android, FreeBSD, Apple targets and Windows. Nothing is registered on other targets.
+/// An import is missing if the linker drops the object defining it, e.g. an unreferenced
+/// module of a dependency, a unit test of the DPI library itself sees all its imports.
+pub fn registered_signatures() -> impl Iterator<Item = &'static DpiSignature> {
+    let mut node = REGISTERED.load(Ordering::Acquire);
+    std::iter::from_fn(move || {
+        // Safety : nodes are `static`, and never removed from the list
+        let registration = unsafe { node.as_ref()? };
+        node = registration.next.load(Ordering::Relaxed);
+        Some(registration.signature)
+    })
+}
+
+/// Types accepted by value in a `#[dpi_import]` function, i.e. `input` arguments
+///
+/// Wrappers in [`param`] are passed through unchanged, so they can still be
+/// used when the idiomatic form does not fit (e.g. open arrays or `output` wrappers).
+pub trait DpiArg<'a>: Sized {
+    /// Argument type of the generated `extern "C"` function
+    type Abi;
+
+    const SV_TYPE: SvType;
+    /// `output` for the `Out*` wrappers, `inout` for the `Inout*` wrappers, otherwise `input`
+    const DIRECTION: Direction = Direction::Input;
+
+    fn from_abi(abi: Self::Abi) -> Self;
+
+    /// Report if `abi` is not a valid encoding, called before [`Self::from_abi`] by
+    /// `#[dpi_import]` in debug builds. `name` is the name of the argument.
+    fn check_abi(_abi: &Self::Abi, _name: &str) {}
+}
+
+/// Types accepted as `&mut T` in a `#[dpi_import]` function, i.e. `output` or `inout` arguments
+///
+/// The value is copied in, passed to the function by reference and copied back on return.
+pub trait DpiOutArg<'a>: Sized {
+    /// Argument type of the generated `extern "C"` function
+    type Abi;
+
+    const SV_TYPE: SvType;
+
+    /// Initial value of an `output` argument, the simulator provided value is not read
+    fn output() -> Self;
+
+    /// Initial value of an `inout` argument
+    fn load(abi: &Self::Abi) -> Self;
+
+    fn store(&self, abi: &mut Self::Abi);
+
+    /// Report if `abi` is not a valid encoding, called before [`Self::load`] by
+    /// `#[dpi_import]` in debug builds. `name` is the name of the argument.
+    fn check_abi(_abi: &Self::Abi, _name: &str) {}
+}
+
+/// Types returned from a `#[dpi_import]` function
+///
+/// DPI results are limited to small types (LRM 2023 35.5.5), so the supported types are:
+/// `()`, [`SvBasicType`]s including `SvTransparent` types, `Option<bool>` as `logic` with
+/// `None` as X, [`BitVec`] up to 32 bits, strings ([`String`], [`CString`], `&'static CStr`,
+/// [`RetStr`]),
+/// `Result<(), Disabled>` for tasks and `Result<T, Disabled>` for basic types.
+/// Other `Option<T>` and wider or 4-state vectors have no encoding, return a sentinel or use
+/// an `output` argument.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be returned from a DPI import",
+    note = "only `()`, basic types, `Option<bool>`, `BitVec` up to 32 bits, strings and `Result<_, Disabled>` can be returned, pass other values by an `output` argument"
+)]
+pub trait DpiRet {
+    /// Return type of the generated `extern "C"` function
+    type Abi;
+
+    /// `None` for `void` functions and tasks
+    const SV_TYPE: Option<SvType>;
+    /// Whether the import is a `task`, returning `1` if disabled
+    const IS_TASK: bool = false;
+
+    fn into_abi(self) -> Self::Abi;
+}
+
+impl<T: SvBasicType> DpiArg<'_> for T {
+    type Abi = In<T>;
+    const SV_TYPE: SvType = SvType::basic::<T>();
+
+    fn from_abi(abi: In<T>) -> Self {
+        abi.get()
+    }
+
+    fn check_abi(abi: &In<T>, name: &str) {
+        check_encoding::<T>(abi.raw(), name);
+    }
+}
+
+// e.g. a `bit` argument declared as `int` in SystemVerilog
+fn check_encoding<T: SvBasicType>(raw: T::Underlying, name: &str) {
+    if T::try_from_underlying(raw).is_none() {
+        report_error(format_args!(
+            "invalid encoding {raw:?} of `{}` argument `{name}`",
+            T::SV_NAME
+        ));
+    }
+}
+
+impl<'a, const BITS: usize> DpiArg<'a> for BitVec<BITS> {
+    type Abi = InBV<'a, BITS>;
+    const SV_TYPE: SvType = SvType::BitVec(packed_width(BITS));
+
+    fn from_abi(abi: InBV<'a, BITS>) -> Self {
+        abi.to_owned()
+    }
+}
+
+impl<'a, const BITS: usize> DpiArg<'a> for LogicVec<BITS> {
+    type Abi = InLV<'a, BITS>;
+    const SV_TYPE: SvType = SvType::LogicVec(packed_width(BITS));
+
+    fn from_abi(abi: InLV<'a, BITS>) -> Self {
+        abi.to_owned()
+    }
+}
+
+impl<'a> DpiArg<'a> for &'a CStr {
+    type Abi = InStr<'a>;
+    const SV_TYPE: SvType = SvType::String;
+
+    fn from_abi(abi: InStr<'a>) -> Self {
+        abi.get()
+    }
+}
+
+/// # Panics
+///
+/// Panics if the string is not valid UTF-8.
+impl<'a> DpiArg<'a> for &'a str {
+    type Abi = InStr<'a>;
+    const SV_TYPE: SvType = SvType::String;
+
+    fn from_abi(abi: InStr<'a>) -> Self {
+        abi.get()
+            .to_str()
+            .expect("string argument is not valid UTF-8")
+    }
+}
+
+// param wrappers are passed through, their own lifetime is unrelated to the trait's
+// so that elided lifetimes in the signature still resolve
+macro_rules! impl_dpi_arg_identity {
+    ([$($generics: tt)*] $T: ty, $sv_type: expr, $direction: ident) => {
+        impl<$($generics)*> DpiArg<'_> for $T {
+            type Abi = Self;
+            const SV_TYPE: SvType = $sv_type;
+            const DIRECTION: Direction = Direction::$direction;
+
+            fn from_abi(abi: Self) -> Self {
+                abi
+            }
+        }
+    };
+}
+
+impl_dpi_arg_identity!([T: SvBasicType] In<T>, SvType::basic::<T>(), Input);
+impl_dpi_arg_identity!(['b, T: SvBasicType] Out<'b, T>, SvType::basic::<T>(), Output);
+impl_dpi_arg_identity!(['b, T: SvBasicType] Inout<'b, T>, SvType::basic::<T>(), Inout);
+impl_dpi_arg_identity!(['b] InStr<'b>, SvType::String, Input);
+impl_dpi_arg_identity!(['b] OutStr<'b>, SvType::String, Output);
+impl_dpi_arg_identity!(['b] InoutStr<'b>, SvType::String, Inout);
+impl_dpi_arg_identity!(['b, const BITS: usize] InBV<'b, BITS>, SvType::BitVec(packed_width(BITS)), Input);
+impl_dpi_arg_identity!(['b, const BITS: usize] OutBV<'b, BITS>, SvType::BitVec(packed_width(BITS)), Output);
+impl_dpi_arg_identity!(['b, const BITS: usize] InLV<'b, BITS>, SvType::LogicVec(packed_width(BITS)), Input);
+impl_dpi_arg_identity!(['b, const BITS: usize] OutLV<'b, BITS>, SvType::LogicVec(packed_width(BITS)), Output);
+impl_dpi_arg_identity!(['b, T: SvArrayElem] InOpenArray<'b, T>, SvType::open_array::<T>(), Input);
+impl_dpi_arg_identity!(['b, T: SvArrayElem] OutOpenArray<'b, T>, SvType::open_array::<T>(), Output);
+impl_dpi_arg_identity!(['b, T: SvScalar] InOpenArrayScalar<'b, T>, SvType::open_array::<T>(), Input);
+impl_dpi_arg_identity!(['b, T: SvScalar] OutOpenArrayScalar<'b, T>, SvType::open_array::<T>(), Output);
+impl_dpi_arg_identity!(['b, const BITS: usize] InOpenArrayBV<'b, BITS>, SvType::BitVecOpenArray(packed_width(BITS)), Input);
+impl_dpi_arg_identity!(['b, const BITS: usize] OutOpenArrayBV<'b, BITS>, SvType::BitVecOpenArray(packed_width(BITS)), Output);
+impl_dpi_arg_identity!(['b, const BITS: usize] InOpenArrayLV<'b, BITS>, SvType::LogicVecOpenArray(packed_width(BITS)), Input);
+impl_dpi_arg_identity!(['b, const BITS: usize] OutOpenArrayLV<'b, BITS>, SvType::LogicVecOpenArray(packed_width(BITS)), Output);
+
+// `Out` and `Inout` share the same layout, `Inout` serves both directions
+impl<'a, T: SvBasicType<Underlying: 'a>> DpiOutArg<'a> for T {
+    type Abi = Inout<'a, T>;
+    const SV_TYPE: SvType = SvType::basic::<T>();
+
+    fn output() -> Self {
+        // Safety : underlying types are C scalars or pointers, for which all-zero is valid.
+        // Basic types decode it lossily, `SvTransparent` requires it not to panic
+        T::from_underlying(unsafe { mem::zeroed() })
+    }
+
+    fn load(abi: &Inout<'a, T>) -> Self {
+        abi.get()
+    }
+
+    fn check_abi(abi: &Inout<'a, T>, name: &str) {
+        check_encoding::<T>(abi.raw(), name);
+    }
+
+    fn store(&self, abi: &mut Inout<'a, T>) {
+        abi.set(*self);
+    }
+}
+
+impl<'a, const BITS: usize> DpiOutArg<'a> for BitVec<BITS> {
+    type Abi = OutBV<'a, BITS>;
+    const SV_TYPE: SvType = SvType::BitVec(packed_width(BITS));
+
+    fn output() -> Self {
+        BitVec::zero()
+    }
+
+    fn load(abi: &OutBV<'a, BITS>) -> Self {
+        abi.to_owned()
+    }
+
+    fn store(&self, abi: &mut OutBV<'a, BITS>) {
+        abi.set(self);
+    }
+}
+
+impl<'a, const BITS: usize> DpiOutArg<'a> for LogicVec<BITS> {
+    type Abi = OutLV<'a, BITS>;
+    const SV_TYPE: SvType = SvType::LogicVec(packed_width(BITS));
+
+    fn output() -> Self {
+        LogicVec::zero()
+    }
+
+    fn load(abi: &OutLV<'a, BITS>) -> Self {
+        abi.to_owned()
+    }
+
+    fn store(&self, abi: &mut OutLV<'a, BITS>) {
+        abi.set(self);
+    }
+}
+
+/// # Panics
+///
+/// Panics if an `inout` string is not valid UTF-8, or the result contains an interior nul byte.
+impl<'a> DpiOutArg<'a> for String {
+    type Abi = InoutStr<'a>;
+    const SV_TYPE: SvType = SvType::String;
+
+    fn output() -> Self {
+        String::new()
+    }
+
+    fn load(abi: &InoutStr<'a>) -> Self {
+        let value = abi.get().to_str();
+        value
+            .expect("string argument is not valid UTF-8")
+            .to_owned()
+    }
+
+    fn store(&self, abi: &mut InoutStr<'a>) {
+        abi.set(self);
+    }
+}
+
+impl DpiRet for () {
+    type Abi = ();
+    const SV_TYPE: Option<SvType> = None;
+
+    fn into_abi(self) {}
+}
+
+impl<T: SvBasicType> DpiRet for T {
+    type Abi = Ret<T>;
+    const SV_TYPE: Option<SvType> = Some(SvType::basic::<T>());
+
+    fn into_abi(self) -> Ret<T> {
+        self.into()
+    }
+}
+
+/// Returned as `logic`, `None` is X
+impl DpiRet for Option<bool> {
+    type Abi = Ret<Logic>;
+    const SV_TYPE: Option<SvType> = Some(SvType::basic::<Logic>());
+
+    fn into_abi(self) -> Ret<Logic> {
+        Logic::from_bool_x(self).into()
+    }
+}
+
+/// Returned as `svBitVecVal`, fails to compile if `BITS > 32`
+impl<const BITS: usize> DpiRet for BitVec<BITS> {
+    type Abi = sys::svBitVecVal;
+    const SV_TYPE: Option<SvType> = Some(SvType::BitVec(packed_ret_width(BITS)));
+
+    fn into_abi(self) -> sys::svBitVecVal {
+        const { packed_ret_width(BITS) };
+        self.as_words()[0]
+    }
+}
+
+impl DpiRet for RetStr {
+    type Abi = RetStr;
+    const SV_TYPE: Option<SvType> = Some(SvType::String);
+
+    fn into_abi(self) -> RetStr {
+        self
+    }
+}
+
+impl DpiRet for &'static CStr {
+    type Abi = RetStr;
+    const SV_TYPE: Option<SvType> = Some(SvType::String);
+
+    fn into_abi(self) -> RetStr {
+        self.into()
+    }
+}
+
+impl DpiRet for CString {
+    type Abi = RetStr;
+    const SV_TYPE: Option<SvType> = Some(SvType::String);
+
+    fn into_abi(self) -> RetStr {
+        self.into()
+    }
+}
+
+/// See [`RetStr::new`]
+impl DpiRet for String {
+    type Abi = RetStr;
+    const SV_TYPE: Option<SvType> = Some(SvType::String);
+
+    fn into_abi(self) -> RetStr {
+        RetStr::new(&self)
+    }
+}
+
+/// An import `task`, returns `1` if disabled, see LRM 2023 35.9
+impl DpiRet for Result<(), Disabled> {
+    type Abi = ffi::c_int;
+    const SV_TYPE: Option<SvType> = None;
+    const IS_TASK: bool = true;
+
+    fn into_abi(self) -> ffi::c_int {
+        self.is_err() as ffi::c_int
+    }
+}
+
+/// An import function, the disable is acknowledged and the result is zero if disabled
+impl<T: SvBasicType> DpiRet for Result<T, Disabled> {
+    type Abi = Ret<T>;
+    const SV_TYPE: Option<SvType> = Some(SvType::basic::<T>());
+
+    fn into_abi(self) -> Ret<T> {
+        match self {
+            Ok(value) => value.into(),
+            Err(disabled) => {
+                disabled.acknowledge();
+                Ret::zeroed()
+            }
+        }
+    }
+}
+
+/// Value returned to the simulator by a `#[dpi_import(catch_panic)]` shim on panic
+#[doc(hidden)]
+pub trait PanicAbi {
+    fn on_panic() -> Self;
+}
+
+impl PanicAbi for () {
+    fn on_panic() {}
+}
+
+/// Not disabled, the simulation is finishing anyway
+impl PanicAbi for ffi::c_int {
+    fn on_panic() -> Self {
+        0
+    }
+}
+
+impl<T: SvBasicType> PanicAbi for Ret<T> {
+    fn on_panic() -> Self {
+        Ret::zeroed()
+    }
+}
+
+/// An empty string, a `string` result shall not be `NULL`
+impl PanicAbi for RetStr {
+    fn on_panic() -> Self {
+        c"".into()
+    }
+}
+
+/// Body of a `#[dpi_import(catch_panic)]` shim, returns [`PanicAbi::on_panic`] to the
+/// simulator on panic. See also [`catch_panic`]
+#[doc(hidden)]
+pub fn catch_panic_abi<A: PanicAbi>(f: impl FnOnce() -> A) -> A {
+    catch_panic(f).unwrap_or_else(A::on_panic)
+}
@@ -0,0 +1,82 @@
+use std::{collections::BTreeMap, sync::Mutex};
+
+use super::*;
+
+/// One `T` per calling scope, e.g. per instance of a module with DPI imports
+///
+/// Declare as a `static`, instances are constructed by `init` on first use in each scope.
+/// Calls on the same registry shall not nest, e.g. from an export calling back into an import
+/// which uses the same registry, otherwise it deadlocks.
+pub struct InstanceRegistry<T> {
+    init: fn(SvScope) -> T,
+    inner: Mutex<Instances<T>>,
+}
+
+struct Instances<T> {
+    // scope address to index into `items`, items are kept in construction order
+    index: BTreeMap<usize, usize>,
+    items: Vec<(SvScope, T)>,
+}
+
+impl<T> InstanceRegistry<T> {
+    pub const fn new(init: fn(SvScope) -> T) -> Self {
+        InstanceRegistry {
+            init,
+            inner: Mutex::new(Instances {
+                index: BTreeMap::new(),
+                items: Vec::new(),
+            }),
+        }
+    }
+
+    /// Run `f` with the instance of the current scope, see [`SvScope::get_current`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no current scope, e.g. called from a non-`context` import
+    pub fn with_current<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        let scope = SvScope::get_current().expect("no current scope, is the import `context`?");
+        self.with(scope, f)
+    }
+
+    /// Run `f` with the instance of `scope`, constructed if not yet
+    pub fn with<R>(&self, scope: SvScope, f: impl FnOnce(&mut T) -> R) -> R {
+        let mut inner = self.inner.lock().unwrap();
+        let key = scope.ptr.as_ptr() as usize;
+        let n = match inner.index.get(&key) {
+            Some(&n) => n,
+            None => {
+                let value = (self.init)(scope);
+                let n = inner.items.len();
+                inner.items.push((scope, value));
+                inner.index.insert(key, n);
+                n
+            }
+        };
+        f(&mut inner.items[n].1)
+    }
+
+    /// Number of constructed instances
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Visit all instances in construction order, e.g. for end-of-test reporting
+    pub fn for_each(&self, mut f: impl FnMut(SvScope, &mut T)) {
+        let mut inner = self.inner.lock().unwrap();
+        for (scope, value) in &mut inner.items {
+            f(*scope, value);
+        }
+    }
+
+    /// Remove all instances, in construction order
+    pub fn take_all(&self) -> Vec<(SvScope, T)> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.index.clear();
+        std::mem::take(&mut inner.items)
+    }
+}
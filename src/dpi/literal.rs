@@ -0,0 +1,509 @@
+// SystemVerilog integer literals (LRM 2023 5.7.1) for packed vector values.
+//
+// Formatting follows `$display` format specifiers (LRM 2023 21.2.1):
+// `{}` is `%d`, `{:x}` is `%h`, `{:o}` is `%o`, `{:b}` is `%b`.
+// The alternate flag `{:#x}` prepends the size and base, e.g. `8'h0f`.
+
+use std::{fmt, str::FromStr};
+
+use super::{BitVec, LogicVec, bits};
+
+/// Error returned when parsing a SystemVerilog literal fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLiteralError {
+    kind: ErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ErrorKind {
+    Empty,
+    InvalidSize,
+    InvalidBase,
+    InvalidDigit(char),
+    TooWide { size: usize, bits: usize },
+    Overflow,
+    NegativeUnknown,
+    Unknown,
+}
+
+impl fmt::Display for ParseLiteralError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ErrorKind::Empty => f.write_str("literal has no digits"),
+            ErrorKind::InvalidSize => f.write_str("invalid literal size"),
+            ErrorKind::InvalidBase => f.write_str("invalid literal base"),
+            ErrorKind::InvalidDigit(c) => write!(f, "invalid digit `{c}` in literal"),
+            ErrorKind::TooWide { size, bits } => {
+                write!(f, "{size}-bit literal does not fit into {bits}-bit vector")
+            }
+            ErrorKind::Overflow => f.write_str("literal value exceeds its size"),
+            ErrorKind::NegativeUnknown => f.write_str("negative literal contains X or Z"),
+            ErrorKind::Unknown => f.write_str("X or Z in two-state literal"),
+        }
+    }
+}
+
+impl std::error::Error for ParseLiteralError {}
+
+fn error<T>(kind: ErrorKind) -> Result<T, ParseLiteralError> {
+    Err(ParseLiteralError { kind })
+}
+
+/// Two planes of a parsed literal, in the encoding of `svLogicVecVal`
+struct Planes {
+    aval: Vec<u32>,
+    bval: Vec<u32>,
+}
+
+impl Planes {
+    fn zero(bits: usize) -> Self {
+        let len = bits.div_ceil(32);
+        Self {
+            aval: vec![0; len],
+            bval: vec![0; len],
+        }
+    }
+
+    // (aval, bval) of bit `i`
+    fn bit(&self, i: usize) -> (bool, bool) {
+        let get = |words: &[u32]| (words[i / 32] >> (i % 32)) & 1 != 0;
+        (get(&self.aval), get(&self.bval))
+    }
+
+    fn set_bit(&mut self, i: usize, (aval, bval): (bool, bool)) {
+        bits::write_bits(&mut self.aval, i, 1, aval as u32);
+        bits::write_bits(&mut self.bval, i, 1, bval as u32);
+    }
+
+    fn fill(&mut self, from: usize, to: usize, value: (bool, bool)) {
+        for i in from..to {
+            self.set_bit(i, value);
+        }
+    }
+}
+
+/// Parse `literal` into a `bits`-bit vector
+fn parse(literal: &str, bits: usize) -> Result<Planes, ParseLiteralError> {
+    let s = literal.trim();
+    let (mut neg, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+
+    let (size, signed, radix, digits) = match s.split_once('\'') {
+        // plain decimal numbers are signed
+        None => (None, true, 10u32, s),
+        Some((size, rest)) => {
+            let size = size.trim().replace('_', "");
+            let size = match size.is_empty() {
+                true => None,
+                false => match size.parse::<usize>() {
+                    Ok(0) | Err(_) => return error(ErrorKind::InvalidSize),
+                    Ok(size) => Some(size),
+                },
+            };
+            let (signed, rest) = match rest.strip_prefix(['s', 'S']) {
+                Some(rest) => (true, rest),
+                None => (false, rest),
+            };
+            let mut chars = rest.chars();
+            let radix = match chars.next() {
+                Some('b' | 'B') => 2,
+                Some('o' | 'O') => 8,
+                Some('d' | 'D') => 10,
+                Some('h' | 'H') => 16,
+                _ => return error(ErrorKind::InvalidBase),
+            };
+            let rest = chars.as_str().trim_start();
+            // also accept the sign after the base, e.g. `16'sd-5`
+            let rest = match rest.strip_prefix('-') {
+                Some(rest) if !neg => {
+                    neg = true;
+                    rest
+                }
+                _ => rest,
+            };
+            (size, signed, radix, rest)
+        }
+    };
+
+    if digits.is_empty() || digits.starts_with('_') {
+        return error(ErrorKind::Empty);
+    }
+    let digits: Vec<char> = digits.chars().filter(|&c| c != '_').collect();
+
+    let width = size.unwrap_or(bits);
+    if width > bits {
+        return error(ErrorKind::TooWide { size: width, bits });
+    }
+
+    let mut planes = match radix {
+        10 => parse_decimal(&digits, width)?,
+        _ => parse_power_of_two(&digits, radix.trailing_zeros() as usize, width)?,
+    };
+
+    // extend to `bits`, signed literals are sign-extended
+    planes.aval.resize(bits.div_ceil(32), 0);
+    planes.bval.resize(bits.div_ceil(32), 0);
+    if signed && width < bits {
+        let msb = planes.bit(width - 1);
+        planes.fill(width, bits, msb);
+    }
+
+    if neg {
+        if planes.bval.iter().any(|&b| b != 0) {
+            return error(ErrorKind::NegativeUnknown);
+        }
+        negate(&mut planes.aval);
+        if let Some(last) = planes.aval.last_mut() {
+            *last &= bits::last_word_mask(bits);
+        }
+    }
+
+    Ok(planes)
+}
+
+// (aval, bval) of an unknown digit, `None` for known digits
+fn unknown_digit(c: char) -> Option<(bool, bool)> {
+    match c {
+        'x' | 'X' => Some((true, true)),
+        'z' | 'Z' | '?' => Some((false, true)),
+        _ => None,
+    }
+}
+
+fn parse_power_of_two(
+    digits: &[char],
+    k: usize,
+    width: usize,
+) -> Result<Planes, ParseLiteralError> {
+    let mut planes = Planes::zero(width);
+    for (n, &c) in digits.iter().rev().enumerate() {
+        let (aval, bval) = match unknown_digit(c) {
+            Some((a, b)) => (if a { !0 } else { 0 }, if b { !0 } else { 0 }),
+            None => match c.to_digit(1 << k) {
+                Some(d) => (d, 0),
+                None => return error(ErrorKind::InvalidDigit(c)),
+            },
+        };
+        let (aval, bval) = (aval & bits::low_mask(k), bval & bits::low_mask(k));
+        let pos = n * k;
+        let w = k.min(width.saturating_sub(pos));
+        // X and Z digits are truncated silently, as they are also used for padding
+        if bval == 0 && aval >> w != 0 {
+            return error(ErrorKind::Overflow);
+        }
+        if w == 0 {
+            continue;
+        }
+        bits::write_bits(&mut planes.aval, pos, w, aval);
+        bits::write_bits(&mut planes.bval, pos, w, bval);
+    }
+
+    // a leftmost X or Z digit pads the literal with X or Z
+    if let Some(pad) = unknown_digit(digits[0]) {
+        planes.fill((digits.len() * k).min(width), width, pad);
+    }
+    Ok(planes)
+}
+
+fn parse_decimal(digits: &[char], width: usize) -> Result<Planes, ParseLiteralError> {
+    let mut planes = Planes::zero(width);
+    if let [c] = digits {
+        if let Some(value) = unknown_digit(*c) {
+            planes.fill(0, width, value);
+            return Ok(planes);
+        }
+    }
+
+    // one more word to detect overflow
+    let mut value = vec![0u32; width.div_ceil(32) + 1];
+    for &c in digits {
+        let Some(d) = c.to_digit(10) else {
+            return error(ErrorKind::InvalidDigit(c));
+        };
+        let mut carry = d as u64;
+        for word in value.iter_mut() {
+            let cur = *word as u64 * 10 + carry;
+            *word = cur as u32;
+            carry = cur >> 32;
+        }
+        if carry != 0 || bits::read_u32(&value, width as isize) != 0 {
+            return error(ErrorKind::Overflow);
+        }
+    }
+    value.pop();
+    planes.aval = value;
+    Ok(planes)
+}
+
+// two's complement in place
+fn negate(words: &mut [u32]) {
+    let mut carry = 1u64;
+    for word in words.iter_mut() {
+        let cur = (!*word) as u64 + carry;
+        *word = cur as u32;
+        carry = cur >> 32;
+    }
+}
+
+/// Digits of `%h`, `%o` or `%b`, `k` bits per digit, MSB first
+fn format_power_of_two(aval: &[u32], bval: Option<&[u32]>, bits: usize, k: usize) -> String {
+    let ndigits = bits.div_ceil(k);
+    (0..ndigits)
+        .rev()
+        .map(|n| {
+            let pos = (n * k) as isize;
+            let mask = bits::low_mask(k.min(bits - n * k));
+            let a = bits::read_u32(aval, pos) & mask;
+            let b = bval.map_or(0, |bval| bits::read_u32(bval, pos) & mask);
+            match b {
+                0 => char::from_digit(a, 1 << k).unwrap(),
+                _ => unknown_char(a, b, mask),
+            }
+        })
+        .collect()
+}
+
+// `x` / `z` if all bits are X / Z, otherwise `X` if any bit is X, `Z` if any bit is Z
+fn unknown_char(a: u32, b: u32, mask: u32) -> char {
+    match (a & b, !a & b) {
+        (x, 0) if x == mask => 'x',
+        (0, z) if z == mask => 'z',
+        (0, _) => 'Z',
+        _ => 'X',
+    }
+}
+
+/// Digits of `%d`
+fn format_decimal(aval: &[u32], bval: Option<&[u32]>, bits: usize) -> String {
+    if let Some(bval) = bval {
+        let mask = |n: usize| match n + 1 == bval.len() {
+            true => bits::last_word_mask(bits),
+            false => !0,
+        };
+        let (mut x_all, mut z_all, mut any) = (true, true, false);
+        for (n, (&a, &b)) in aval.iter().zip(bval).enumerate() {
+            let mask = mask(n);
+            x_all &= a & b == mask;
+            z_all &= !a & b & mask == mask;
+            any |= b != 0;
+        }
+        if any {
+            let has_x = aval.iter().zip(bval).any(|(&a, &b)| a & b != 0);
+            return match (x_all, z_all, has_x) {
+                (true, _, _) => "x",
+                (_, true, _) => "z",
+                (_, _, true) => "X",
+                _ => "Z",
+            }
+            .to_string();
+        }
+    }
+
+    // repeatedly divide by 10^9
+    let mut value = aval.to_vec();
+    let mut chunks = Vec::new();
+    loop {
+        let mut rem = 0u64;
+        for word in value.iter_mut().rev() {
+            let cur = (rem << 32) | *word as u64;
+            *word = (cur / 1_000_000_000) as u32;
+            rem = cur % 1_000_000_000;
+        }
+        chunks.push(rem as u32);
+        if value.iter().all(|&w| w == 0) {
+            break;
+        }
+    }
+    let mut s = chunks.pop().unwrap().to_string();
+    for chunk in chunks.iter().rev() {
+        s.push_str(&format!("{chunk:09}"));
+    }
+    s
+}
+
+macro_rules! impl_fmt {
+    ($V: ident, |$v: ident| ($aval: expr, $bval: expr)) => {
+        impl<const BITS: usize> fmt::Display for $V<BITS> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let $v = self;
+                let digits = format_decimal($aval, $bval, BITS);
+                f.pad_integral(true, &format!("{BITS}'d"), &digits)
+            }
+        }
+
+        impl<const BITS: usize> fmt::LowerHex for $V<BITS> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let $v = self;
+                let digits = format_power_of_two($aval, $bval, BITS, 4);
+                f.pad_integral(true, &format!("{BITS}'h"), &digits)
+            }
+        }
+
+        impl<const BITS: usize> fmt::Octal for $V<BITS> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let $v = self;
+                let digits = format_power_of_two($aval, $bval, BITS, 3);
+                f.pad_integral(true, &format!("{BITS}'o"), &digits)
+            }
+        }
+
+        impl<const BITS: usize> fmt::Binary for $V<BITS> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let $v = self;
+                let digits = format_power_of_two($aval, $bval, BITS, 1);
+                f.pad_integral(true, &format!("{BITS}'b"), &digits)
+            }
+        }
+    };
+}
+
+impl_fmt!(BitVec, |v| (v.as_words(), None));
+impl_fmt!(LogicVec, |v| (
+    v.aval().as_words(),
+    Some(v.bval().as_words())
+));
+
+/// Parse a SystemVerilog integer literal, e.g. `8'hff`, `'b1010`, `16'sd-5`, `-3` or `42`.
+///
+/// Unsized literals take the width of the vector. Sized literals wider than `BITS`
+/// or values exceeding the literal size are rejected. Signed literals are sign-extended.
+impl<const BITS: usize> FromStr for BitVec<BITS> {
+    type Err = ParseLiteralError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let planes = parse(s, BITS)?;
+        if planes.bval.iter().any(|&b| b != 0) {
+            return error(ErrorKind::Unknown);
+        }
+        Ok(BitVec::from_words(&planes.aval))
+    }
+}
+
+/// Parse a SystemVerilog integer literal, e.g. `8'b10xz_0101`, `'hDEAD_BEEF`, `16'sd-5`.
+///
+/// X and Z digits are accepted in binary, octal and hex literals, and as the only digit
+/// of decimal literals. A leftmost X or Z digit pads the literal to its size with X or Z.
+/// Other rules are the same as [`BitVec`].
+impl<const BITS: usize> FromStr for LogicVec<BITS> {
+    type Err = ParseLiteralError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let planes = parse(s, BITS)?;
+        Ok(LogicVec::from_planes(
+            BitVec::from_words(&planes.aval),
+            BitVec::from_words(&planes.bval),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bv<const BITS: usize>(s: &str) -> BitVec<BITS> {
+        s.parse().unwrap()
+    }
+
+    fn lv<const BITS: usize>(s: &str) -> LogicVec<BITS> {
+        s.parse().unwrap()
+    }
+
+    fn kind<T: FromStr<Err = ParseLiteralError>>(s: &str) -> ErrorKind {
+        s.parse::<T>().err().unwrap().kind
+    }
+
+    #[test]
+    fn parse_bases() {
+        assert_eq!(bv::<8>("8'hff"), BitVec::from(0xffu8));
+        assert_eq!(bv::<8>("8'b1010_0101"), BitVec::from(0xa5u8));
+        assert_eq!(bv::<9>("9'o777"), BitVec::from(0x1ffu16));
+        assert_eq!(bv::<16>("16'd65535"), BitVec::from(0xffffu16));
+        assert_eq!(bv::<16>("'hDEAD"), BitVec::from(0xdeadu16));
+        assert_eq!(bv::<16>("42"), BitVec::from(42u8));
+        assert_eq!(bv::<100>("100'h8_0000_0000_0000_0000_0000_0001"), {
+            BitVec::from(1u8) | (BitVec::from(1u8) << 99)
+        });
+    }
+
+    #[test]
+    fn parse_signed() {
+        assert_eq!(bv::<8>("-3"), BitVec::from(0xfdu8));
+        assert_eq!(bv::<16>("16'sd-5"), BitVec::from(0xfffbu16));
+        assert_eq!(bv::<16>("-16'sd5"), BitVec::from(0xfffbu16));
+        // signed literals are sign-extended, unsigned ones zero-extended
+        assert_eq!(bv::<16>("4'sb1000"), BitVec::from(0xfff8u16));
+        assert_eq!(bv::<16>("4'b1000"), BitVec::from(0x0008u16));
+        assert_eq!(bv::<70>("-1"), BitVec::ones());
+    }
+
+    #[test]
+    fn parse_unknown() {
+        let v = lv::<8>("8'b10xz_0101");
+        assert_eq!(format!("{v:b}"), "10xz0101");
+        assert_eq!(lv::<8>("8'hx"), LogicVec::x());
+        assert_eq!(lv::<40>("'dz"), LogicVec::z());
+        assert_eq!(format!("{:b}", lv::<8>("8'bz1")), "zzzzzzz1");
+        assert_eq!(format!("{:b}", lv::<8>("8'b?1")), "zzzzzzz1");
+        assert_eq!(format!("{:b}", lv::<8>("8'b01")), "00000001");
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(kind::<BitVec<8>>(""), ErrorKind::Empty);
+        assert_eq!(kind::<BitVec<8>>("8'h"), ErrorKind::Empty);
+        assert_eq!(kind::<BitVec<8>>("0'h1"), ErrorKind::InvalidSize);
+        assert_eq!(kind::<BitVec<8>>("8'q1"), ErrorKind::InvalidBase);
+        assert_eq!(kind::<BitVec<8>>("8'b102"), ErrorKind::InvalidDigit('2'));
+        assert_eq!(
+            kind::<BitVec<8>>("9'h1"),
+            ErrorKind::TooWide { size: 9, bits: 8 }
+        );
+        assert_eq!(kind::<BitVec<8>>("4'h1f"), ErrorKind::Overflow);
+        assert_eq!(kind::<BitVec<8>>("8'd256"), ErrorKind::Overflow);
+        assert_eq!(kind::<BitVec<8>>("8'hx"), ErrorKind::Unknown);
+        assert_eq!(kind::<LogicVec<8>>("-8'hx"), ErrorKind::NegativeUnknown);
+        assert_eq!(kind::<LogicVec<8>>("8'd1x"), ErrorKind::InvalidDigit('x'));
+    }
+
+    #[test]
+    fn format() {
+        let v = BitVec::<12>::from(0xabcu16);
+        assert_eq!(format!("{v}"), "2748");
+        assert_eq!(format!("{v:x}"), "abc");
+        assert_eq!(format!("{v:#x}"), "12'habc");
+        assert_eq!(format!("{v:o}"), "5274");
+        assert_eq!(format!("{v:#b}"), "12'b101010111100");
+        assert_eq!(format!("{:x}", BitVec::<10>::from(0x3ffu16)), "3ff");
+        let big = BitVec::<128>::from_u128(u128::MAX);
+        assert_eq!(format!("{big}"), u128::MAX.to_string());
+    }
+
+    #[test]
+    fn format_unknown() {
+        assert_eq!(format!("{:x}", lv::<8>("8'b1x1x_zzzz")), "Xz");
+        assert_eq!(format!("{:x}", lv::<8>("8'bzz01_xxxx")), "Zx");
+        assert_eq!(format!("{}", LogicVec::<40>::x()), "x");
+        assert_eq!(format!("{}", LogicVec::<40>::z()), "z");
+        assert_eq!(format!("{}", lv::<8>("8'b1x")), "X");
+        assert_eq!(format!("{}", lv::<8>("8'b1z")), "Z");
+    }
+
+    #[test]
+    fn round_trip() {
+        for s in [
+            "70'h3f_0123_4567_89ab_cdef",
+            "70'b1",
+            "70'd12345678901234567890",
+        ] {
+            let v = bv::<70>(s);
+            assert_eq!(bv::<70>(&format!("{v:#x}")), v);
+            assert_eq!(bv::<70>(&format!("{v:#o}")), v);
+            assert_eq!(bv::<70>(&format!("{v:#b}")), v);
+            assert_eq!(bv::<70>(&format!("{v:#}")), v);
+        }
+        let v = lv::<12>("12'b1xz0_0101_zzzz");
+        assert_eq!(lv::<12>(&format!("{v:#b}")), v);
+    }
+}
@@ -0,0 +1,498 @@
+use std::{
+    fmt,
+    ops::{
+        Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Mul,
+        MulAssign, Neg, Not, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign,
+    },
+};
+
+use super::{BitVec, Logic, bits::impl_binary_op};
+
+/// Owned four-state packed vector, the value counterpart of `logic [BITS-1:0]`
+///
+/// Stored as `aval` / `bval` planes like [`crate::sys::dpi::svLogicVecVal`]:
+/// `(0, 0)` is `0`, `(1, 0)` is `1`, `(0, 1)` is Z, `(1, 1)` is X.
+///
+/// Operators follow SystemVerilog X/Z propagation rules (LRM 2023 11.4):
+/// - bitwise operators resolve per bit, e.g. `0 & X` is `0`, `1 | Z` is `1`
+/// - arithmetic operators yield all X if any operand bit is X or Z
+///
+/// `==` on this type is the case equality `===`, see [`Self::sv_eq`] for logical equality.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct LogicVec<const BITS: usize> {
+    aval: BitVec<BITS>,
+    bval: BitVec<BITS>,
+}
+
+impl<const BITS: usize> LogicVec<BITS> {
+    /// All bits are `0`
+    pub fn zero() -> Self {
+        Self::from_planes(BitVec::zero(), BitVec::zero())
+    }
+
+    /// All bits are X
+    pub fn x() -> Self {
+        Self::splat(Logic::X)
+    }
+
+    /// All bits are Z
+    pub fn z() -> Self {
+        Self::splat(Logic::Z)
+    }
+
+    /// All bits are `value`
+    pub fn splat(value: Logic) -> Self {
+        let plane = |set: bool| if set { BitVec::ones() } else { BitVec::zero() };
+        let (aval, bval) = value.to_avbv();
+        Self::from_planes(plane(aval), plane(bval))
+    }
+
+    pub fn from_planes(aval: BitVec<BITS>, bval: BitVec<BITS>) -> Self {
+        Self { aval, bval }
+    }
+
+    pub fn aval(&self) -> &BitVec<BITS> {
+        &self.aval
+    }
+
+    pub fn bval(&self) -> &BitVec<BITS> {
+        &self.bval
+    }
+
+    pub fn into_planes(self) -> (BitVec<BITS>, BitVec<BITS>) {
+        (self.aval, self.bval)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `i >= BITS`
+    pub fn bit(&self, i: usize) -> Logic {
+        Logic::from_avbv(self.aval.bit(i), self.bval.bit(i))
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `i >= BITS`
+    pub fn set_bit(&mut self, i: usize, value: Logic) {
+        let (aval, bval) = value.to_avbv();
+        self.aval.set_bit(i, aval);
+        self.bval.set_bit(i, bval);
+    }
+
+    /// Part select `[lsb +: W]`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lsb + W > BITS`
+    pub fn part<const W: usize>(&self, lsb: usize) -> LogicVec<W> {
+        LogicVec::from_planes(self.aval.part(lsb), self.bval.part(lsb))
+    }
+
+    /// Assign part select `[lsb +: W]`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lsb + W > BITS`
+    pub fn set_part<const W: usize>(&mut self, lsb: usize, value: &LogicVec<W>) {
+        self.aval.set_part(lsb, &value.aval);
+        self.bval.set_part(lsb, &value.bval);
+    }
+
+    /// Zero-extend or truncate to another width
+    pub fn resize<const W: usize>(&self) -> LogicVec<W> {
+        LogicVec::from_planes(self.aval.resize(), self.bval.resize())
+    }
+
+    /// Whether any bit is X or Z
+    pub fn has_x_or_z(&self) -> bool {
+        !self.bval.is_zero()
+    }
+
+    /// Whether any bit is X
+    pub fn has_x(&self) -> bool {
+        !(&self.aval & &self.bval).is_zero()
+    }
+
+    /// Whether any bit is Z
+    pub fn has_z(&self) -> bool {
+        !(!&self.aval & &self.bval).is_zero()
+    }
+
+    /// Two-state value, `None` if any bit is X or Z
+    pub fn to_bitvec(&self) -> Option<BitVec<BITS>> {
+        (!self.has_x_or_z()).then(|| self.aval.clone())
+    }
+
+    /// Two-state value, X and Z are mapped to `0`
+    pub fn to_bitvec_lossy(&self) -> BitVec<BITS> {
+        &self.aval & !&self.bval
+    }
+
+    // bits known to be 0 / 1
+    fn known0(&self) -> BitVec<BITS> {
+        !(&self.aval | &self.bval)
+    }
+
+    fn known1(&self) -> BitVec<BITS> {
+        &self.aval & !&self.bval
+    }
+
+    // bits not in `known0` nor `known1` become X
+    fn from_known(known0: BitVec<BITS>, known1: BitVec<BITS>) -> Self {
+        let bval = !(&known0 | &known1);
+        Self::from_planes(!known0, bval)
+    }
+
+    fn and(&self, other: &Self) -> Self {
+        Self::from_known(
+            self.known0() | other.known0(),
+            self.known1() & other.known1(),
+        )
+    }
+
+    fn or(&self, other: &Self) -> Self {
+        Self::from_known(
+            self.known0() & other.known0(),
+            self.known1() | other.known1(),
+        )
+    }
+
+    fn xor(&self, other: &Self) -> Self {
+        let unknown = &self.bval | &other.bval;
+        Self::from_planes((&self.aval ^ &other.aval) | &unknown, unknown)
+    }
+
+    /// Reduction AND `&v`
+    pub fn reduce_and(&self) -> Logic {
+        if !self.known0().is_zero() {
+            Logic::Value0
+        } else if self.has_x_or_z() {
+            Logic::X
+        } else {
+            Logic::Value1
+        }
+    }
+
+    /// Reduction OR `|v`
+    pub fn reduce_or(&self) -> Logic {
+        if !self.known1().is_zero() {
+            Logic::Value1
+        } else if self.has_x_or_z() {
+            Logic::X
+        } else {
+            Logic::Value0
+        }
+    }
+
+    /// Reduction XOR `^v`
+    pub fn reduce_xor(&self) -> Logic {
+        match self.to_bitvec() {
+            Some(value) => (value.count_ones() % 2 == 1).into(),
+            None => Logic::X,
+        }
+    }
+
+    /// Logical equality `==`, X if the result is ambiguous due to X or Z bits
+    pub fn sv_eq(&self, other: &Self) -> Logic {
+        let differ = (self.known0() & other.known1()) | (self.known1() & other.known0());
+        if !differ.is_zero() {
+            Logic::Value0
+        } else if self.has_x_or_z() || other.has_x_or_z() {
+            Logic::X
+        } else {
+            Logic::Value1
+        }
+    }
+
+    /// Logical inequality `!=`
+    pub fn sv_ne(&self, other: &Self) -> Logic {
+        !self.sv_eq(other)
+    }
+
+    // X if any operand bit is X or Z, otherwise compare as unsigned
+    fn relational(
+        &self,
+        other: &Self,
+        f: impl FnOnce(&BitVec<BITS>, &BitVec<BITS>) -> bool,
+    ) -> Logic {
+        match (self.to_bitvec(), other.to_bitvec()) {
+            (Some(a), Some(b)) => f(&a, &b).into(),
+            _ => Logic::X,
+        }
+    }
+
+    /// Unsigned `<`
+    pub fn sv_lt(&self, other: &Self) -> Logic {
+        self.relational(other, |a, b| a < b)
+    }
+
+    /// Unsigned `<=`
+    pub fn sv_le(&self, other: &Self) -> Logic {
+        self.relational(other, |a, b| a <= b)
+    }
+
+    /// Unsigned `>`
+    pub fn sv_gt(&self, other: &Self) -> Logic {
+        self.relational(other, |a, b| a > b)
+    }
+
+    /// Unsigned `>=`
+    pub fn sv_ge(&self, other: &Self) -> Logic {
+        self.relational(other, |a, b| a >= b)
+    }
+
+    // all X if any operand bit is X or Z
+    fn arithmetic(
+        &self,
+        other: &Self,
+        f: impl FnOnce(&BitVec<BITS>, &BitVec<BITS>) -> BitVec<BITS>,
+    ) -> Self {
+        match (self.to_bitvec(), other.to_bitvec()) {
+            (Some(a), Some(b)) => f(&a, &b).into(),
+            _ => Self::x(),
+        }
+    }
+
+    fn sv_add(&self, other: &Self) -> Self {
+        self.arithmetic(other, BitVec::wrapping_add)
+    }
+
+    fn sv_sub(&self, other: &Self) -> Self {
+        self.arithmetic(other, BitVec::wrapping_sub)
+    }
+
+    fn sv_mul(&self, other: &Self) -> Self {
+        self.arithmetic(other, BitVec::wrapping_mul)
+    }
+}
+
+impl<const BITS: usize> Default for LogicVec<BITS> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<const BITS: usize> fmt::Debug for LogicVec<BITS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "LogicVec<{BITS}>({self:#b})")
+    }
+}
+
+impl<const BITS: usize> From<BitVec<BITS>> for LogicVec<BITS> {
+    fn from(value: BitVec<BITS>) -> Self {
+        Self::from_planes(value, BitVec::zero())
+    }
+}
+
+impl<const BITS: usize> From<&BitVec<BITS>> for LogicVec<BITS> {
+    fn from(value: &BitVec<BITS>) -> Self {
+        value.clone().into()
+    }
+}
+
+/// Zero-extended to `BITS` bits
+impl<const BITS: usize> From<Logic> for LogicVec<BITS> {
+    fn from(value: Logic) -> Self {
+        let mut result = Self::zero();
+        result.set_bit(0, value);
+        result
+    }
+}
+
+impl From<LogicVec<1>> for Logic {
+    fn from(value: LogicVec<1>) -> Self {
+        value.bit(0)
+    }
+}
+
+impl<const BITS: usize> Not for &LogicVec<BITS> {
+    type Output = LogicVec<BITS>;
+    fn not(self) -> LogicVec<BITS> {
+        LogicVec::from_planes(!&self.aval | &self.bval, self.bval.clone())
+    }
+}
+
+impl<const BITS: usize> Not for LogicVec<BITS> {
+    type Output = LogicVec<BITS>;
+    fn not(self) -> LogicVec<BITS> {
+        !&self
+    }
+}
+
+/// All X if any bit is X or Z
+impl<const BITS: usize> Neg for &LogicVec<BITS> {
+    type Output = LogicVec<BITS>;
+    fn neg(self) -> LogicVec<BITS> {
+        match self.to_bitvec() {
+            Some(value) => (-value).into(),
+            None => LogicVec::x(),
+        }
+    }
+}
+
+impl<const BITS: usize> Neg for LogicVec<BITS> {
+    type Output = LogicVec<BITS>;
+    fn neg(self) -> LogicVec<BITS> {
+        -&self
+    }
+}
+
+impl_binary_op!(LogicVec, BitAnd, bitand, BitAndAssign, bitand_assign, and);
+impl_binary_op!(LogicVec, BitOr, bitor, BitOrAssign, bitor_assign, or);
+impl_binary_op!(LogicVec, BitXor, bitxor, BitXorAssign, bitxor_assign, xor);
+impl_binary_op!(LogicVec, Add, add, AddAssign, add_assign, sv_add);
+impl_binary_op!(LogicVec, Sub, sub, SubAssign, sub_assign, sv_sub);
+impl_binary_op!(LogicVec, Mul, mul, MulAssign, mul_assign, sv_mul);
+
+/// Logical shift left, vacated bits are `0`
+impl<const BITS: usize> Shl<usize> for &LogicVec<BITS> {
+    type Output = LogicVec<BITS>;
+    fn shl(self, n: usize) -> LogicVec<BITS> {
+        LogicVec::from_planes(&self.aval << n, &self.bval << n)
+    }
+}
+
+/// Logical shift right, vacated bits are `0`
+impl<const BITS: usize> Shr<usize> for &LogicVec<BITS> {
+    type Output = LogicVec<BITS>;
+    fn shr(self, n: usize) -> LogicVec<BITS> {
+        LogicVec::from_planes(&self.aval >> n, &self.bval >> n)
+    }
+}
+
+impl<const BITS: usize> Shl<usize> for LogicVec<BITS> {
+    type Output = LogicVec<BITS>;
+    fn shl(self, n: usize) -> LogicVec<BITS> {
+        &self << n
+    }
+}
+
+impl<const BITS: usize> Shr<usize> for LogicVec<BITS> {
+    type Output = LogicVec<BITS>;
+    fn shr(self, n: usize) -> LogicVec<BITS> {
+        &self >> n
+    }
+}
+
+impl<const BITS: usize> ShlAssign<usize> for LogicVec<BITS> {
+    fn shl_assign(&mut self, n: usize) {
+        *self = &*self << n;
+    }
+}
+
+impl<const BITS: usize> ShrAssign<usize> for LogicVec<BITS> {
+    fn shr_assign(&mut self, n: usize) {
+        *self = &*self >> n;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Logic::{Value0 as L0, Value1 as L1, X, Z};
+
+    const VALUES: [Logic; 4] = [L0, L1, X, Z];
+
+    // LRM 2023 Table 11-7, rows and columns in the order of `VALUES`
+    const AND: [[Logic; 4]; 4] = [
+        [L0, L0, L0, L0],
+        [L0, L1, X, X],
+        [L0, X, X, X],
+        [L0, X, X, X],
+    ];
+    const OR: [[Logic; 4]; 4] = [
+        [L0, L1, X, X],
+        [L1, L1, L1, L1],
+        [X, L1, X, X],
+        [X, L1, X, X],
+    ];
+    const XOR: [[Logic; 4]; 4] = [[L0, L1, X, X], [L1, L0, X, X], [X, X, X, X], [X, X, X, X]];
+    const NOT: [Logic; 4] = [L1, L0, X, X];
+
+    // every pair of `VALUES` in its own bit, spread over several words
+    fn lanes<const BITS: usize>(f: impl Fn(usize, usize) -> Logic) -> LogicVec<BITS> {
+        let mut v = LogicVec::zero();
+        for i in 0..16 {
+            v.set_bit(5 * i + 7, f(i / 4, i % 4));
+        }
+        v
+    }
+
+    #[test]
+    fn truth_tables() {
+        let a = lanes::<100>(|i, _| VALUES[i]);
+        let b = lanes::<100>(|_, j| VALUES[j]);
+        assert_eq!(a.clone() & b.clone(), lanes(|i, j| AND[i][j]));
+        assert_eq!(a.clone() | b.clone(), lanes(|i, j| OR[i][j]));
+        assert_eq!(a.clone() ^ b.clone(), lanes(|i, j| XOR[i][j]));
+        let not = !&a;
+        for i in 0..16 {
+            assert_eq!(not.bit(5 * i + 7), NOT[i / 4]);
+        }
+    }
+
+    #[test]
+    fn scalar_matches_vector() {
+        for (i, &a) in VALUES.iter().enumerate() {
+            assert_eq!(!a, NOT[i]);
+            for (j, &b) in VALUES.iter().enumerate() {
+                assert_eq!(a & b, AND[i][j], "{a:?} & {b:?}");
+                assert_eq!(a | b, OR[i][j], "{a:?} | {b:?}");
+                assert_eq!(a ^ b, XOR[i][j], "{a:?} ^ {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn reductions() {
+        let mut v = LogicVec::<40>::from(BitVec::ones());
+        assert_eq!(v.reduce_and(), L1);
+        assert_eq!(v.reduce_xor(), L0);
+        v.set_bit(35, Z);
+        assert_eq!(v.reduce_and(), X);
+        assert_eq!(v.reduce_or(), L1);
+        assert_eq!(v.reduce_xor(), X);
+        v.set_bit(3, L0);
+        assert_eq!(v.reduce_and(), L0);
+        assert_eq!(LogicVec::<40>::z().reduce_or(), X);
+        assert_eq!(LogicVec::<40>::zero().reduce_or(), L0);
+    }
+
+    #[test]
+    fn comparisons() {
+        let a = LogicVec::<40>::from(BitVec::from(5u8));
+        let mut b = LogicVec::<40>::from(BitVec::from(4u8));
+        assert_eq!(a.sv_eq(&a), L1);
+        assert_eq!(a.sv_gt(&b), L1);
+        b.set_bit(39, X);
+        // bit 0 differs in known values
+        assert_eq!(a.sv_eq(&b), L0);
+        assert_eq!(a.sv_ne(&b), L1);
+        assert_eq!(a.sv_lt(&b), X);
+        b.set_bit(0, L1);
+        assert_eq!(a.sv_eq(&b), X);
+    }
+
+    #[test]
+    fn arithmetic_with_unknown_is_x() {
+        let a = LogicVec::<40>::from(BitVec::from(5u8));
+        assert_eq!(a.clone() + a.clone(), LogicVec::from(BitVec::from(10u8)));
+        let mut b = a.clone();
+        b.set_bit(20, Z);
+        assert_eq!(a.clone() + b.clone(), LogicVec::x());
+        assert_eq!(-b, LogicVec::x());
+    }
+
+    #[test]
+    fn x_and_z_detection() {
+        let mut v = LogicVec::<70>::zero();
+        assert!(!v.has_x_or_z());
+        v.set_bit(65, Z);
+        assert!(v.has_z() && !v.has_x());
+        assert_eq!(v.to_bitvec(), None);
+        assert!(v.to_bitvec_lossy().is_zero());
+        v.set_bit(1, X);
+        assert!(v.has_x());
+        assert_eq!((v >> 1).bit(0), X);
+    }
+}
@@ -0,0 +1,140 @@
+use super::{BitVec, bits, param::InBV, param::OutBV};
+
+#[cfg(feature = "macros")]
+pub use svdpi_macros::SvPacked;
+
+/// Types which can be a field of a packed struct
+pub trait SvPackedField: Sized {
+    /// Natural width in bits
+    const WIDTH: usize;
+
+    /// Whether the field can be narrowed by `#[bits(N)]`, i.e. `width` may be less than
+    /// [`Self::WIDTH`]. Only integers and [`BitVec`] are narrowable.
+    const NARROWABLE: bool = false;
+
+    /// Read `width` bits starting at `lsb`, `width` shall not exceed [`Self::WIDTH`],
+    /// and shall equal it unless [`Self::NARROWABLE`]
+    fn read_bits(words: &[u32], lsb: usize, width: usize) -> Self;
+
+    /// Write low `width` bits starting at `lsb`, `width` shall not exceed [`Self::WIDTH`],
+    /// and shall equal it unless [`Self::NARROWABLE`]
+    fn write_bits(&self, words: &mut [u32], lsb: usize, width: usize);
+}
+
+/// A packed struct, usually implemented by `#[derive(SvPacked)]` with `macros` feature
+///
+/// Fields are laid out MSB first, i.e. the first field occupies the most significant bits
+/// (LRM 2023 7.2.1). Each field is a [`SvPackedField`] of its natural width,
+/// or an integer or [`BitVec`] narrowed by `#[bits(N)]`. Signed integers are sign-extended.
+pub trait SvPacked: SvPackedField {
+    /// Decode from a `bit [N-1:0]` argument, `N` shall equal [`SvPackedField::WIDTH`]
+    fn from_bv<const N: usize>(bv: InBV<'_, N>) -> Self {
+        const { assert!(N == Self::WIDTH, "packed struct width mismatch") };
+        Self::read_bits(bv.as_slice(), 0, N)
+    }
+
+    /// Encode into a `bit [N-1:0]` argument, `N` shall equal [`SvPackedField::WIDTH`]
+    fn write_to<const N: usize>(&self, bv: &mut OutBV<'_, N>) {
+        const { assert!(N == Self::WIDTH, "packed struct width mismatch") };
+        bv.set(&self.to_bitvec());
+    }
+
+    /// Decode from an owned vector, `N` shall equal [`SvPackedField::WIDTH`]
+    fn from_bitvec<const N: usize>(value: &BitVec<N>) -> Self {
+        const { assert!(N == Self::WIDTH, "packed struct width mismatch") };
+        Self::read_bits(value.as_words(), 0, N)
+    }
+
+    /// Encode into an owned vector, `N` shall equal [`SvPackedField::WIDTH`]
+    fn to_bitvec<const N: usize>(&self) -> BitVec<N> {
+        const { assert!(N == Self::WIDTH, "packed struct width mismatch") };
+        let mut words = vec![0; N.div_ceil(32)];
+        self.write_bits(&mut words, 0, N);
+        BitVec::from_words(&words)
+    }
+}
+
+fn read_u128(words: &[u32], lsb: usize, width: usize) -> u128 {
+    let mut value = [0; 4];
+    bits::read_range(words, lsb, width, &mut value[..width.div_ceil(32)]);
+    value
+        .iter()
+        .enumerate()
+        .fold(0, |acc, (i, &w)| acc | (w as u128) << (32 * i))
+}
+
+fn write_u128(words: &mut [u32], lsb: usize, width: usize, value: u128) {
+    let value: [u32; 4] = std::array::from_fn(|i| (value >> (32 * i)) as u32);
+    bits::write_range(words, lsb, width, &value);
+}
+
+macro_rules! impl_packed_uint {
+    ($($T: ty),*) => {
+        $(
+            impl SvPackedField for $T {
+                const WIDTH: usize = <$T>::BITS as usize;
+                const NARROWABLE: bool = true;
+
+                fn read_bits(words: &[u32], lsb: usize, width: usize) -> Self {
+                    read_u128(words, lsb, width) as $T
+                }
+
+                fn write_bits(&self, words: &mut [u32], lsb: usize, width: usize) {
+                    write_u128(words, lsb, width, *self as u128);
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_packed_sint {
+    ($($T: ty),*) => {
+        $(
+            impl SvPackedField for $T {
+                const WIDTH: usize = <$T>::BITS as usize;
+                const NARROWABLE: bool = true;
+
+                fn read_bits(words: &[u32], lsb: usize, width: usize) -> Self {
+                    let value = read_u128(words, lsb, width);
+                    // sign-extend from `width` bits
+                    let shift = 128 - width as u32;
+                    (((value << shift) as i128) >> shift) as $T
+                }
+
+                fn write_bits(&self, words: &mut [u32], lsb: usize, width: usize) {
+                    write_u128(words, lsb, width, *self as u128);
+                }
+            }
+        )*
+    };
+}
+
+impl_packed_uint!(u8, u16, u32, u64, u128);
+impl_packed_sint!(i8, i16, i32, i64, i128);
+
+impl SvPackedField for bool {
+    const WIDTH: usize = 1;
+
+    fn read_bits(words: &[u32], lsb: usize, _width: usize) -> Self {
+        bits::read_u32(words, lsb as isize) & 1 != 0
+    }
+
+    fn write_bits(&self, words: &mut [u32], lsb: usize, _width: usize) {
+        bits::write_bits(words, lsb, 1, *self as u32);
+    }
+}
+
+impl<const W: usize> SvPackedField for BitVec<W> {
+    const WIDTH: usize = W;
+    const NARROWABLE: bool = true;
+
+    fn read_bits(words: &[u32], lsb: usize, width: usize) -> Self {
+        let mut value = vec![0; W.div_ceil(32)];
+        bits::read_range(words, lsb, width, &mut value[..width.div_ceil(32)]);
+        BitVec::from_words(&value)
+    }
+
+    fn write_bits(&self, words: &mut [u32], lsb: usize, width: usize) {
+        bits::write_range(words, lsb, width, self.as_words());
+    }
+}
@@ -2,15 +2,32 @@ use std::{ffi, marker::PhantomData};
 
 use super::*;
 
+mod open_array;
+pub use open_array::*;
+
+#[cfg(feature = "macros")]
+pub use svdpi_macros::{SvEnum, SvNewtype};
+
 #[repr(transparent)]
 pub struct In<T: SvBasicType> {
     inner: T::Underlying,
 }
 
 impl<T: SvBasicType> In<T> {
+    /// Lossy for invalid encodings, see [`SvBasicType::from_underlying`]
     pub fn get(&self) -> T {
         T::from_underlying(self.inner)
     }
+
+    /// `None` for invalid encodings, see [`SvBasicType::try_from_underlying`]
+    pub fn try_get(&self) -> Option<T> {
+        T::try_from_underlying(self.inner)
+    }
+
+    /// The value as passed by the simulator
+    pub fn raw(&self) -> T::Underlying {
+        self.inner
+    }
 }
 
 #[repr(transparent)]
@@ -30,10 +47,21 @@ pub struct Inout<'a, T: SvBasicType> {
 }
 
 impl<T: SvBasicType> Inout<'_, T> {
+    /// Lossy for invalid encodings, see [`SvBasicType::from_underlying`]
     pub fn get(&self) -> T {
         T::from_underlying(*self.ptr)
     }
 
+    /// `None` for invalid encodings, see [`SvBasicType::try_from_underlying`]
+    pub fn try_get(&self) -> Option<T> {
+        T::try_from_underlying(*self.ptr)
+    }
+
+    /// The value as passed by the simulator
+    pub fn raw(&self) -> T::Underlying {
+        *self.ptr
+    }
+
     pub fn set(&mut self, value: T) {
         *self.ptr = value.to_underlying();
     }
@@ -45,6 +73,16 @@ pub struct Ret<T: SvBasicType> {
     inner: T::Underlying,
 }
 
+impl<T: SvBasicType> Ret<T> {
+    /// The all-zero encoding, without decoding it as `T`
+    pub(crate) fn zeroed() -> Self {
+        // Safety : underlying types are C scalars or pointers, for which all-zero is valid
+        Ret {
+            inner: unsafe { std::mem::zeroed() },
+        }
+    }
+}
+
 impl<T: SvBasicType> From<T> for Ret<T> {
     fn from(value: T) -> Self {
         Ret {
@@ -54,23 +92,37 @@ impl<T: SvBasicType> From<T> for Ret<T> {
 }
 
 // we use supertrait sealed trait trick
-// to prevent downstream implements SvBasicType
+// to prevent downstream implements SvBasicType, use SvTransparent instead
 trait SvBasicTypePriv {}
 
 #[allow(private_bounds)]
 pub trait SvBasicType: Copy + SvBasicTypePriv {
-    type Underlying: Copy;
+    type Underlying: Copy + std::fmt::Debug;
+
+    /// SystemVerilog type name, e.g. `int unsigned`
+    const SV_NAME: &'static str;
+    /// C type name as used in `svdpi.h`, e.g. `unsigned int`
+    const C_NAME: &'static str;
 
+    /// Decode a value passed by the simulator. Invalid encodings are decoded lossily
+    /// instead of panicking, e.g. non-zero as `true` for `bool` and `X` for [`Logic`].
     fn from_underlying(x: Self::Underlying) -> Self;
     fn to_underlying(self) -> Self::Underlying;
+
+    /// `None` if `x` is not a valid encoding, e.g. `2` for `bool`
+    fn try_from_underlying(x: Self::Underlying) -> Option<Self> {
+        Some(Self::from_underlying(x))
+    }
 }
 
 // $T and $U are actually the same, no need for any conversion
 macro_rules! impl_sv_basic_type {
-    ($T: ty, $U: ty) => {
+    ($T: ty, $U: ty, $sv: literal, $c: literal) => {
         impl SvBasicTypePriv for $T {}
         impl SvBasicType for $T {
             type Underlying = $U;
+            const SV_NAME: &'static str = $sv;
+            const C_NAME: &'static str = $c;
             fn from_underlying(x: Self::Underlying) -> Self {
                 x
             }
@@ -82,16 +134,26 @@ macro_rules! impl_sv_basic_type {
 }
 
 // See LRM 2023 Table H.1 (Annex H) - Mapping Data Types
-impl_sv_basic_type!(i8, ffi::c_char); // byte
-impl_sv_basic_type!(u8, ffi::c_uchar); // byte unsigned
-impl_sv_basic_type!(i16, ffi::c_short); // shortint
-impl_sv_basic_type!(u16, ffi::c_ushort); // shortint unsigned
-impl_sv_basic_type!(i32, ffi::c_int); // int
-impl_sv_basic_type!(u32, ffi::c_uint); // int unsigned
-impl_sv_basic_type!(i64, ffi::c_longlong); // int
-impl_sv_basic_type!(u64, ffi::c_ulonglong); // int unsigned
-impl_sv_basic_type!(f64, ffi::c_double); // real
-impl_sv_basic_type!(f32, ffi::c_float); // shortreal
+impl_sv_basic_type!(i8, ffi::c_char, "byte", "char");
+impl_sv_basic_type!(u8, ffi::c_uchar, "byte unsigned", "unsigned char");
+impl_sv_basic_type!(i16, ffi::c_short, "shortint", "short int");
+impl_sv_basic_type!(
+    u16,
+    ffi::c_ushort,
+    "shortint unsigned",
+    "unsigned short int"
+);
+impl_sv_basic_type!(i32, ffi::c_int, "int", "int");
+impl_sv_basic_type!(u32, ffi::c_uint, "int unsigned", "unsigned int");
+impl_sv_basic_type!(i64, ffi::c_longlong, "longint", "long long");
+impl_sv_basic_type!(
+    u64,
+    ffi::c_ulonglong,
+    "longint unsigned",
+    "unsigned long long"
+);
+impl_sv_basic_type!(f64, ffi::c_double, "real", "double");
+impl_sv_basic_type!(f32, ffi::c_float, "shortreal", "float");
 
 fn _assert_type_equality() {
     trait Identity {
@@ -119,12 +181,11 @@ fn _assert_type_equality() {
 // encodings are defined in svdpi.h, sv_0 / sv_1
 impl SvBasicType for bool {
     type Underlying = ffi::c_uchar;
+    const SV_NAME: &'static str = "bit";
+    const C_NAME: &'static str = "svBit";
+    // non-zero is true, as in C
     fn from_underlying(x: Self::Underlying) -> Self {
-        match x {
-            0 => false,
-            1 => true,
-            _ => unreachable!(),
-        }
+        x != 0
     }
     fn to_underlying(self) -> Self::Underlying {
         match self {
@@ -132,29 +193,44 @@ impl SvBasicType for bool {
             false => 0,
         }
     }
+    fn try_from_underlying(x: Self::Underlying) -> Option<Self> {
+        match x {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
 }
 impl SvBasicTypePriv for bool {}
 
 // encodings are defined in svdpi.h, sv_0 / sv_1 / sv_z / sv_x
 impl SvBasicType for Logic {
     type Underlying = ffi::c_uchar;
+    const SV_NAME: &'static str = "logic";
+    const C_NAME: &'static str = "svLogic";
+    // invalid encodings are unknown
     fn from_underlying(x: Self::Underlying) -> Self {
-        match x {
-            0 => Logic::Value0,
-            1 => Logic::Value1,
-            2 => Logic::Z,
-            3 => Logic::X,
-            _ => unreachable!(),
-        }
+        Self::try_from_underlying(x).unwrap_or(Logic::X)
     }
     fn to_underlying(self) -> Self::Underlying {
         self.into()
     }
+    fn try_from_underlying(x: Self::Underlying) -> Option<Self> {
+        match x {
+            0 => Some(Logic::Value0),
+            1 => Some(Logic::Value1),
+            2 => Some(Logic::Z),
+            3 => Some(Logic::X),
+            _ => None,
+        }
+    }
 }
 impl SvBasicTypePriv for Logic {}
 
 impl<T> SvBasicType for *mut T {
     type Underlying = *mut c_void;
+    const SV_NAME: &'static str = "chandle";
+    const C_NAME: &'static str = "void*";
 
     fn from_underlying(x: Self::Underlying) -> Self {
         x as *mut T
@@ -166,6 +242,57 @@ impl<T> SvBasicType for *mut T {
 }
 impl<T> SvBasicTypePriv for *mut T {}
 
+/// A user type passed as the basic type `Repr`, e.g. a fieldless enum or a newtype.
+///
+/// Implemented by `#[derive(SvEnum)]` or `#[derive(SvNewtype)]` with `macros` feature,
+/// every `SvTransparent` type is a [`SvBasicType`].
+///
+/// # Safety
+///
+/// `Self` shall have the same size and alignment as `Repr`, i.e. a `#[repr(transparent)]`
+/// struct or a `#[repr(int)]` enum. `from_repr` shall be sound for every value of `Repr`,
+/// which may be any encoding coming from the simulator.
+///
+/// `from_repr` of the all-zero `Repr` is the initial value of `output` arguments, it should
+/// not panic. `#[derive(SvEnum)]` requires a variant of `0` or a `#[fallback]` for this.
+pub unsafe trait SvTransparent: Copy {
+    type Repr: SvBasicType;
+
+    fn from_repr(repr: Self::Repr) -> Self;
+    fn to_repr(self) -> Self::Repr;
+
+    /// `None` if `repr` is not a valid encoding, e.g. not any variant of an enum
+    fn try_from_repr(repr: Self::Repr) -> Option<Self> {
+        Some(Self::from_repr(repr))
+    }
+}
+
+impl<T: SvTransparent> SvBasicType for T {
+    type Underlying = <T::Repr as SvBasicType>::Underlying;
+    const SV_NAME: &'static str = T::Repr::SV_NAME;
+    const C_NAME: &'static str = T::Repr::C_NAME;
+
+    fn from_underlying(x: Self::Underlying) -> Self {
+        T::from_repr(T::Repr::from_underlying(x))
+    }
+
+    fn to_underlying(self) -> Self::Underlying {
+        self.to_repr().to_underlying()
+    }
+
+    fn try_from_underlying(x: Self::Underlying) -> Option<Self> {
+        T::Repr::try_from_underlying(x).and_then(T::try_from_repr)
+    }
+}
+impl<T: SvTransparent> SvBasicTypePriv for T {}
+
+/// An out-of-range encoding of an enum derived by `SvEnum`, see [`SvTransparent::from_repr`]
+#[doc(hidden)]
+#[track_caller]
+pub fn invalid_enum_encoding(ty: &str, repr: impl std::fmt::Display) -> ! {
+    panic!("invalid encoding {repr} for enum `{ty}`")
+}
+
 #[repr(transparent)]
 pub struct InStr<'a> {
     inner: *const c_char,
@@ -179,6 +306,65 @@ impl<'a> InStr<'a> {
     }
 }
 
+/// `output string` argument, passed as `const char**`
+///
+/// Strings set from Rust are kept alive on the current thread until the next call begins,
+/// as required by LRM 2023 Annex H, see [`RetStr::new`].
+#[repr(transparent)]
+pub struct OutStr<'a> {
+    ptr: &'a mut *const c_char,
+}
+
+impl OutStr<'_> {
+    /// # Panics
+    ///
+    /// Panics if `value` contains an interior nul byte.
+    pub fn set(&mut self, value: &str) {
+        *self.ptr = str_arena::store(CString::new(value).unwrap());
+    }
+
+    pub fn set_cstr(&mut self, value: &CStr) {
+        *self.ptr = str_arena::store(value.to_owned());
+    }
+
+    /// Set without copying
+    pub fn set_static(&mut self, value: &'static CStr) {
+        *self.ptr = value.as_ptr();
+    }
+}
+
+/// `inout string` argument, passed as `const char**`
+///
+/// Strings set from Rust are kept alive on the current thread until the next call begins,
+/// as required by LRM 2023 Annex H, see [`RetStr::new`].
+#[repr(transparent)]
+pub struct InoutStr<'a> {
+    ptr: &'a mut *const c_char,
+}
+
+impl InoutStr<'_> {
+    pub fn get(&self) -> &CStr {
+        // Safety : InoutStr must point to a valid C-style string
+        unsafe { CStr::from_ptr(*self.ptr) }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `value` contains an interior nul byte.
+    pub fn set(&mut self, value: &str) {
+        *self.ptr = str_arena::store(CString::new(value).unwrap());
+    }
+
+    pub fn set_cstr(&mut self, value: &CStr) {
+        *self.ptr = str_arena::store(value.to_owned());
+    }
+
+    /// Set without copying
+    pub fn set_static(&mut self, value: &'static CStr) {
+        *self.ptr = value.as_ptr();
+    }
+}
+
 #[repr(transparent)]
 pub struct RetStr {
     inner: *const c_char,
@@ -192,7 +378,38 @@ impl From<&'static CStr> for RetStr {
     }
 }
 
+/// The string is moved into a per-thread arena, see [`RetStr::new`]
+impl From<CString> for RetStr {
+    fn from(value: CString) -> Self {
+        Self {
+            inner: str_arena::store(value),
+        }
+    }
+}
+
 impl RetStr {
+    /// Return a dynamically built string.
+    ///
+    /// The string is copied into a per-thread arena and stays valid until the next DPI call,
+    /// as LRM 2023 Annex H allows. Strings are freed when the next call marked by
+    /// [`begin_call`] begins, as `#[dpi_import]` shims do. Without it only the last 64
+    /// strings are kept, so memory use does not grow over the simulation either way.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` contains an interior nul byte.
+    pub fn new(value: &str) -> Self {
+        CString::new(value).unwrap().into()
+    }
+
+    /// Same as [`RetStr::new`], but from a C-style string
+    pub fn from_cstr(value: &CStr) -> Self {
+        value.to_owned().into()
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` shall be a valid pointer to a C-style string, which outlives the DPI call
     pub unsafe fn from_ptr_unchecked(ptr: *const c_char) -> Self {
         Self { inner: ptr }
     }
@@ -206,10 +423,9 @@ pub struct InBV<'a, const BITS: usize> {
 }
 
 impl<'a, const BITS: usize> InBV<'a, BITS> {
-    const U32_LEN: usize = (BITS + 31) / 32;
+    const U32_LEN: usize = BITS.div_ceil(32);
 
-    #[cfg(target_endian = "little")]
-    const U8_LEN: usize = (BITS + 7) / 8;
+    const U8_LEN: usize = BITS.div_ceil(8);
 
     pub fn as_ptr(&self) -> *const u32 {
         self.inner
@@ -234,6 +450,51 @@ impl<'a, const BITS: usize> InBV<'a, BITS> {
     pub fn as_u8_slice(&self) -> &'a [u8] {
         unsafe { std::slice::from_raw_parts(self.inner as *const u8, Self::U8_LEN) }
     }
+
+    /// Copy into an owned value, unused top bits are cleared
+    pub fn to_owned(&self) -> BitVec<BITS> {
+        BitVec::from_words(self.as_slice())
+    }
+
+    /// Same as `svGetBitselBit`, but implemented in Rust
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i >= BITS`
+    pub fn bit(&self, i: usize) -> bool {
+        bv_bit(self.as_slice(), BITS, i)
+    }
+
+    /// Read `width` bits starting at `lsb`, same as `svGetPartselBit` but up to 64 bits
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width > 64` or `lsb + width > BITS`
+    pub fn get_range(&self, lsb: usize, width: usize) -> u64 {
+        bv_get_range(self.as_slice(), BITS, lsb, width)
+    }
+
+    /// Bytes LSB first, available on every target. See also [`Self::as_u8_slice`]
+    pub fn bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        bv_bytes(self.as_slice(), BITS)
+    }
+
+    /// Copy bytes LSB first. See also [`Self::as_u8_slice`]
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        self.bytes().collect()
+    }
+
+    /// Copy bytes LSB first. See also [`Self::as_u8_array`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `LEN` is not `BITS.div_ceil(8)`
+    pub fn to_le_array<const LEN: usize>(&self) -> [u8; LEN] {
+        assert_eq!(LEN, Self::U8_LEN);
+        let mut array = [0; LEN];
+        array.iter_mut().zip(self.bytes()).for_each(|(d, s)| *d = s);
+        array
+    }
 }
 
 #[repr(transparent)]
@@ -243,10 +504,9 @@ pub struct OutBV<'a, const BITS: usize> {
 }
 
 impl<const BITS: usize> OutBV<'_, BITS> {
-    const U32_LEN: usize = (BITS + 31) / 32;
+    const U32_LEN: usize = BITS.div_ceil(32);
 
-    #[cfg(target_endian = "little")]
-    const U8_LEN: usize = (BITS + 7) / 8;
+    const U8_LEN: usize = BITS.div_ceil(8);
 
     pub fn as_ptr(&self) -> *mut u32 {
         self.inner
@@ -291,6 +551,570 @@ impl<const BITS: usize> OutBV<'_, BITS> {
     pub fn as_u8_slice_mut(&mut self) -> &mut [u8] {
         unsafe { std::slice::from_raw_parts_mut(self.inner as *mut u8, Self::U8_LEN) }
     }
+
+    /// Copy into an owned value, unused top bits are cleared
+    pub fn to_owned(&self) -> BitVec<BITS> {
+        BitVec::from_words(self.as_slice())
+    }
+
+    /// Copy from an owned value, unused top bits are written as zero
+    pub fn set(&mut self, value: &BitVec<BITS>) {
+        self.as_slice_mut().copy_from_slice(value.as_words());
+    }
+
+    /// Same as `svGetBitselBit`, but implemented in Rust
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i >= BITS`
+    pub fn bit(&self, i: usize) -> bool {
+        bv_bit(self.as_slice(), BITS, i)
+    }
+
+    /// Same as `svPutBitselBit`, but implemented in Rust
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i >= BITS`
+    pub fn set_bit(&mut self, i: usize, value: bool) {
+        assert!(i < BITS, "bit index {i} out of range for {BITS}-bit vector");
+        bits::write_bits(self.as_slice_mut(), i, 1, value as u32);
+    }
+
+    /// Read `width` bits starting at `lsb`, same as `svGetPartselBit` but up to 64 bits
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width > 64` or `lsb + width > BITS`
+    pub fn get_range(&self, lsb: usize, width: usize) -> u64 {
+        bv_get_range(self.as_slice(), BITS, lsb, width)
+    }
+
+    /// Bytes LSB first, available on every target. See also [`Self::as_u8_slice`]
+    pub fn bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        bv_bytes(self.as_slice(), BITS)
+    }
+
+    /// Copy bytes LSB first. See also [`Self::as_u8_slice`]
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        self.bytes().collect()
+    }
+
+    /// Copy bytes LSB first. See also [`Self::as_u8_array`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `LEN` is not `BITS.div_ceil(8)`
+    pub fn to_le_array<const LEN: usize>(&self) -> [u8; LEN] {
+        assert_eq!(LEN, Self::U8_LEN);
+        let mut array = [0; LEN];
+        array.iter_mut().zip(self.bytes()).for_each(|(d, s)| *d = s);
+        array
+    }
+
+    /// Copy from bytes LSB first, available on every target.
+    /// See also [`Self::as_u8_slice_mut`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes.len()` is not `BITS.div_ceil(8)`
+    pub fn set_le_bytes(&mut self, bytes: &[u8]) {
+        assert_eq!(bytes.len(), Self::U8_LEN);
+        let words = self.as_slice_mut();
+        for (word, chunk) in words.iter_mut().zip(bytes.chunks(4)) {
+            let mut buf = [0; 4];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            *word = u32::from_le_bytes(buf);
+        }
+        if let Some(last) = words.last_mut() {
+            *last &= bits::last_word_mask(BITS);
+        }
+    }
+
+    /// Write low `width` bits of `value` starting at `lsb`,
+    /// same as `svPutPartselBit` but up to 64 bits. Higher bits of `value` are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width > 64` or `lsb + width > BITS`
+    pub fn set_range(&mut self, lsb: usize, width: usize, value: u64) {
+        check_range(BITS, lsb, width);
+        let words = self.as_slice_mut();
+        if width > 32 {
+            bits::write_bits(words, lsb, 32, value as u32);
+            bits::write_bits(words, lsb + 32, width - 32, (value >> 32) as u32);
+        } else if width > 0 {
+            bits::write_bits(words, lsb, width, value as u32);
+        }
+    }
 }
 
 pub type InoutBV<'a, const BITS: usize> = OutBV<'a, BITS>;
+
+fn bv_bit(words: &[u32], bits: usize, i: usize) -> bool {
+    assert!(i < bits, "bit index {i} out of range for {bits}-bit vector");
+    (words[i / 32] >> (i % 32)) & 1 != 0
+}
+
+fn bv_bytes(words: &[u32], bits: usize) -> impl Iterator<Item = u8> + '_ {
+    words
+        .iter()
+        .flat_map(|w| w.to_le_bytes())
+        .take(bits.div_ceil(8))
+}
+
+fn check_range(bits: usize, lsb: usize, width: usize) {
+    assert!(width <= 64, "range width {width} exceeds 64 bits");
+    assert!(
+        lsb + width <= bits,
+        "range [{lsb} +: {width}] out of range for {bits}-bit vector"
+    );
+}
+
+fn bv_get_range(words: &[u32], bits: usize, lsb: usize, width: usize) -> u64 {
+    check_range(bits, lsb, width);
+    let lo = bits::read_u32(words, lsb as isize) as u64;
+    let hi = bits::read_u32(words, lsb as isize + 32) as u64;
+    let value = lo | (hi << 32);
+    match width {
+        64 => value,
+        _ => value & ((1 << width) - 1),
+    }
+}
+
+fn lv_bit(words: &[sys::svLogicVecVal], bits: usize, i: usize) -> Logic {
+    assert!(i < bits, "bit index {i} out of range for {bits}-bit vector");
+    let word = words[i / 32];
+    let shift = i % 32;
+    Logic::from_avbv((word.aval >> shift) & 1 != 0, (word.bval >> shift) & 1 != 0)
+}
+
+fn lv_set_bit(words: &mut [sys::svLogicVecVal], bits: usize, i: usize, value: Logic) {
+    assert!(i < bits, "bit index {i} out of range for {bits}-bit vector");
+    let word = &mut words[i / 32];
+    let shift = i % 32;
+    let (aval, bval) = value.to_avbv();
+    word.aval = (word.aval & !(1 << shift)) | ((aval as u32) << shift);
+    word.bval = (word.bval & !(1 << shift)) | ((bval as u32) << shift);
+}
+
+// bval plane with unused bits masked out, X/Z bits are set
+fn lv_bvals(words: &[sys::svLogicVecVal], bits: usize) -> impl Iterator<Item = u32> + '_ {
+    let len = words.len();
+    words.iter().enumerate().map(move |(n, w)| {
+        if n + 1 == len {
+            w.bval & bits::last_word_mask(bits)
+        } else {
+            w.bval
+        }
+    })
+}
+
+fn lv_avals(words: &[sys::svLogicVecVal], bits: usize) -> impl Iterator<Item = u32> + '_ {
+    let len = words.len();
+    words.iter().enumerate().map(move |(n, w)| {
+        if n + 1 == len {
+            w.aval & bits::last_word_mask(bits)
+        } else {
+            w.aval
+        }
+    })
+}
+
+// X bits have aval set, Z bits have aval clear
+fn lv_has(words: &[sys::svLogicVecVal], bits: usize, aval: bool) -> bool {
+    lv_avals(words, bits)
+        .zip(lv_bvals(words, bits))
+        .any(|(a, b)| (if aval { a & b } else { !a & b }) != 0)
+}
+
+fn lv_to_words_lossy(words: &[sys::svLogicVecVal], bits: usize) -> Vec<u32> {
+    lv_avals(words, bits)
+        .zip(lv_bvals(words, bits))
+        .map(|(a, b)| a & !b)
+        .collect()
+}
+
+fn lv_to_owned<const BITS: usize>(words: &[sys::svLogicVecVal]) -> LogicVec<BITS> {
+    let aval: Vec<_> = words.iter().map(|w| w.aval).collect();
+    let bval: Vec<_> = words.iter().map(|w| w.bval).collect();
+    LogicVec::from_planes(BitVec::from_words(&aval), BitVec::from_words(&bval))
+}
+
+/// `input logic [BITS-1:0]` argument, passed as `const svLogicVecVal*`
+///
+/// Bit `i` refers to the `i`-th bit counting from LSB, regardless of declared range.
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct InLV<'a, const BITS: usize> {
+    inner: *const sys::svLogicVecVal,
+    phantom: PhantomData<&'a [sys::svLogicVecVal]>,
+}
+
+impl<'a, const BITS: usize> InLV<'a, BITS> {
+    const U32_LEN: usize = BITS.div_ceil(32);
+
+    pub fn as_ptr(&self) -> *const sys::svLogicVecVal {
+        self.inner
+    }
+
+    pub fn as_array<const LEN: usize>(&self) -> &'a [sys::svLogicVecVal; LEN] {
+        assert_eq!(LEN, Self::U32_LEN);
+        unsafe { &*(self.inner as *const [sys::svLogicVecVal; LEN]) }
+    }
+
+    pub fn as_slice(&self) -> &'a [sys::svLogicVecVal] {
+        unsafe { std::slice::from_raw_parts(self.inner, Self::U32_LEN) }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `i >= BITS`
+    pub fn bit(&self, i: usize) -> Logic {
+        lv_bit(self.as_slice(), BITS, i)
+    }
+
+    /// `aval` plane, one word per 32 bits, unused top bits are cleared
+    pub fn avals(&self) -> impl Iterator<Item = u32> + 'a {
+        lv_avals(self.as_slice(), BITS)
+    }
+
+    /// `bval` plane, one word per 32 bits, unused top bits are cleared
+    pub fn bvals(&self) -> impl Iterator<Item = u32> + 'a {
+        lv_bvals(self.as_slice(), BITS)
+    }
+
+    /// Whether any bit is X or Z
+    pub fn has_x_or_z(&self) -> bool {
+        self.bvals().any(|b| b != 0)
+    }
+
+    /// Whether any bit is X
+    pub fn has_x(&self) -> bool {
+        lv_has(self.as_slice(), BITS, true)
+    }
+
+    /// Whether any bit is Z
+    pub fn has_z(&self) -> bool {
+        lv_has(self.as_slice(), BITS, false)
+    }
+
+    /// Two-state value in the same layout as [`InBV::as_slice`], X and Z are mapped to `0`
+    pub fn to_words_lossy(&self) -> Vec<u32> {
+        lv_to_words_lossy(self.as_slice(), BITS)
+    }
+
+    /// Two-state value in the same layout as [`InBV::as_slice`], `None` if any bit is X or Z
+    pub fn to_words(&self) -> Option<Vec<u32>> {
+        (!self.has_x_or_z()).then(|| self.to_words_lossy())
+    }
+
+    /// Copy into an owned value, unused top bits are cleared
+    pub fn to_owned(&self) -> LogicVec<BITS> {
+        lv_to_owned(self.as_slice())
+    }
+}
+
+/// `output logic [BITS-1:0]` argument, passed as `svLogicVecVal*`
+///
+/// Bit `i` refers to the `i`-th bit counting from LSB, regardless of declared range.
+#[repr(transparent)]
+pub struct OutLV<'a, const BITS: usize> {
+    inner: *mut sys::svLogicVecVal,
+    phantom: PhantomData<&'a mut [sys::svLogicVecVal]>,
+}
+
+impl<const BITS: usize> OutLV<'_, BITS> {
+    const U32_LEN: usize = BITS.div_ceil(32);
+
+    pub fn as_ptr(&self) -> *mut sys::svLogicVecVal {
+        self.inner
+    }
+
+    pub fn as_array<const LEN: usize>(&self) -> &[sys::svLogicVecVal; LEN] {
+        assert_eq!(LEN, Self::U32_LEN);
+        unsafe { &*(self.inner as *const [sys::svLogicVecVal; LEN]) }
+    }
+
+    pub fn as_array_mut<const LEN: usize>(&mut self) -> &mut [sys::svLogicVecVal; LEN] {
+        assert_eq!(LEN, Self::U32_LEN);
+        unsafe { &mut *(self.inner as *mut [sys::svLogicVecVal; LEN]) }
+    }
+
+    pub fn as_slice(&self) -> &[sys::svLogicVecVal] {
+        unsafe { std::slice::from_raw_parts(self.inner, Self::U32_LEN) }
+    }
+
+    pub fn as_slice_mut(&mut self) -> &mut [sys::svLogicVecVal] {
+        unsafe { std::slice::from_raw_parts_mut(self.inner, Self::U32_LEN) }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `i >= BITS`
+    pub fn bit(&self, i: usize) -> Logic {
+        lv_bit(self.as_slice(), BITS, i)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `i >= BITS`
+    pub fn set_bit(&mut self, i: usize, value: Logic) {
+        lv_set_bit(self.as_slice_mut(), BITS, i, value);
+    }
+
+    /// `aval` plane, one word per 32 bits, unused top bits are cleared
+    pub fn avals(&self) -> impl Iterator<Item = u32> + '_ {
+        lv_avals(self.as_slice(), BITS)
+    }
+
+    /// `bval` plane, one word per 32 bits, unused top bits are cleared
+    pub fn bvals(&self) -> impl Iterator<Item = u32> + '_ {
+        lv_bvals(self.as_slice(), BITS)
+    }
+
+    /// Whether any bit is X or Z
+    pub fn has_x_or_z(&self) -> bool {
+        self.bvals().any(|b| b != 0)
+    }
+
+    /// Whether any bit is X
+    pub fn has_x(&self) -> bool {
+        lv_has(self.as_slice(), BITS, true)
+    }
+
+    /// Whether any bit is Z
+    pub fn has_z(&self) -> bool {
+        lv_has(self.as_slice(), BITS, false)
+    }
+
+    /// Two-state value in the same layout as [`OutBV::as_slice`], X and Z are mapped to `0`
+    pub fn to_words_lossy(&self) -> Vec<u32> {
+        lv_to_words_lossy(self.as_slice(), BITS)
+    }
+
+    /// Two-state value in the same layout as [`OutBV::as_slice`], `None` if any bit is X or Z
+    pub fn to_words(&self) -> Option<Vec<u32>> {
+        (!self.has_x_or_z()).then(|| self.to_words_lossy())
+    }
+
+    /// Set from a two-state value in the same layout as [`OutBV::as_slice`],
+    /// unused top bits are written as zero
+    ///
+    /// # Panics
+    ///
+    /// Panics if `words.len()` mismatches
+    pub fn set_words(&mut self, words: &[u32]) {
+        assert_eq!(words.len(), Self::U32_LEN);
+        for (dst, &aval) in self.as_slice_mut().iter_mut().zip(words) {
+            *dst = sys::svLogicVecVal { aval, bval: 0 };
+        }
+        self.mask_last_word();
+    }
+
+    /// Copy into an owned value, unused top bits are cleared
+    pub fn to_owned(&self) -> LogicVec<BITS> {
+        lv_to_owned(self.as_slice())
+    }
+
+    /// Copy from an owned value, unused top bits are written as zero
+    pub fn set(&mut self, value: &LogicVec<BITS>) {
+        let (aval, bval) = (value.aval().as_words(), value.bval().as_words());
+        for (n, dst) in self.as_slice_mut().iter_mut().enumerate() {
+            *dst = sys::svLogicVecVal {
+                aval: aval[n],
+                bval: bval[n],
+            };
+        }
+    }
+
+    /// Set every bit to `value`, unused top bits are written as zero
+    pub fn fill(&mut self, value: Logic) {
+        let (aval, bval) = value.to_avbv();
+        let word = sys::svLogicVecVal {
+            aval: if aval { !0 } else { 0 },
+            bval: if bval { !0 } else { 0 },
+        };
+        self.as_slice_mut().fill(word);
+        self.mask_last_word();
+    }
+
+    fn mask_last_word(&mut self) {
+        let mask = bits::last_word_mask(BITS);
+        if let Some(last) = self.as_slice_mut().last_mut() {
+            last.aval &= mask;
+            last.bval &= mask;
+        }
+    }
+}
+
+pub type InoutLV<'a, const BITS: usize> = OutLV<'a, BITS>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn out_lv<const BITS: usize>(words: &mut [sys::svLogicVecVal]) -> OutLV<'_, BITS> {
+        OutLV {
+            inner: words.as_mut_ptr(),
+            phantom: PhantomData,
+        }
+    }
+
+    fn in_bv<const BITS: usize>(words: &[u32]) -> InBV<'_, BITS> {
+        assert_eq!(words.len(), BITS.div_ceil(32));
+        InBV {
+            inner: words.as_ptr(),
+            phantom: PhantomData,
+        }
+    }
+
+    fn out_bv<const BITS: usize>(words: &mut [u32]) -> OutBV<'_, BITS> {
+        assert_eq!(words.len(), BITS.div_ceil(32));
+        OutBV {
+            inner: words.as_mut_ptr(),
+            phantom: PhantomData,
+        }
+    }
+
+    #[test]
+    fn bv_bits() {
+        let words = [0x8000_0001, 0x0000_0002, 0x1];
+        let bv = in_bv::<65>(&words);
+        assert!(bv.bit(0) && bv.bit(31) && bv.bit(33) && bv.bit(64));
+        assert!(!bv.bit(1) && !bv.bit(32) && !bv.bit(63));
+
+        let mut words = [0; 3];
+        let mut bv = out_bv::<65>(&mut words);
+        bv.set_bit(64, true);
+        bv.set_bit(31, true);
+        bv.set_bit(31, false);
+        bv.set_bit(32, true);
+        assert_eq!(words, [0, 1, 1]);
+    }
+
+    #[test]
+    fn bv_get_range_across_words() {
+        let words = [0x89ab_cdef, 0x0123_4567, 0x5];
+        let bv = in_bv::<67>(&words);
+        assert_eq!(bv.get_range(0, 64), 0x0123_4567_89ab_cdef);
+        assert_eq!(bv.get_range(28, 8), 0x78);
+        assert_eq!(bv.get_range(16, 32), 0x4567_89ab);
+        assert_eq!(bv.get_range(3, 64), 0xa024_68ac_f135_79bd);
+        // `lsb + width == BITS`
+        assert_eq!(bv.get_range(60, 7), 0x50);
+        assert_eq!(bv.get_range(67, 0), 0);
+        assert_eq!(bv.get_range(5, 0), 0);
+    }
+
+    #[test]
+    fn bv_set_range_across_words() {
+        let mut words = [0; 3];
+        let mut bv = out_bv::<67>(&mut words);
+        bv.set_range(3, 64, 0xa024_68ac_f135_79bd);
+        assert_eq!(bv.get_range(3, 64), 0xa024_68ac_f135_79bd);
+        assert_eq!(bv.get_range(0, 3), 0);
+        bv.set_range(28, 8, 0x1ff);
+        assert_eq!(bv.get_range(28, 8), 0xff);
+        assert_eq!(bv.get_range(36, 4), 0x6);
+        bv.set_range(67, 0, !0);
+        bv.set_range(60, 7, 0);
+        assert_eq!(words, [0xf9ab_cde8, 0x0123_456f, 0]);
+    }
+
+    #[test]
+    #[should_panic = "out of range"]
+    fn bv_bit_out_of_range() {
+        in_bv::<40>(&[0; 2]).bit(40);
+    }
+
+    #[test]
+    #[should_panic = "out of range"]
+    fn bv_set_bit_out_of_range() {
+        out_bv::<40>(&mut [0; 2]).set_bit(40, true);
+    }
+
+    #[test]
+    #[should_panic = "out of range"]
+    fn bv_range_past_end() {
+        in_bv::<40>(&[0; 2]).get_range(33, 8);
+    }
+
+    #[test]
+    #[should_panic = "exceeds 64 bits"]
+    fn bv_range_too_wide() {
+        out_bv::<100>(&mut [0; 4]).set_range(0, 65, 0);
+    }
+
+    // write bytes with the unused top bits set, check every byte view agrees
+    fn check_le_bytes<const BITS: usize>() -> Vec<u32> {
+        let bytes: Vec<u8> = (0..BITS.div_ceil(8))
+            .map(|i| 0xa5 ^ (i as u8).wrapping_mul(0x3b))
+            .collect();
+        let mut expected = bytes.clone();
+        if BITS % 8 != 0 {
+            *expected.last_mut().unwrap() &= (1 << (BITS % 8)) - 1;
+        }
+        assert_ne!(bytes, expected);
+
+        let mut words = vec![!0; BITS.div_ceil(32)];
+        let mut out = out_bv::<BITS>(&mut words);
+        out.set_le_bytes(&bytes);
+        assert_eq!(out.to_le_bytes(), expected);
+        assert!(out.bytes().eq(expected.iter().copied()));
+        #[cfg(target_endian = "little")]
+        assert_eq!(out.as_u8_slice(), expected);
+        assert_eq!(words.last().unwrap() & !bits::last_word_mask(BITS), 0);
+
+        let bv = in_bv::<BITS>(&words);
+        assert_eq!(bv.to_le_bytes(), expected);
+        assert!(bv.bytes().eq(expected.iter().copied()));
+        #[cfg(target_endian = "little")]
+        assert_eq!(bv.as_u8_slice(), expected);
+        assert_eq!(bv.to_owned(), BitVec::<BITS>::from_le_bytes(&bytes));
+        assert_eq!(
+            BitVec::<BITS>::from_le_bytes(&bytes).to_le_bytes(),
+            expected
+        );
+        words
+    }
+
+    #[test]
+    fn bv_le_bytes_round_trip() {
+        let words = check_le_bytes::<12>();
+        assert_eq!(in_bv::<12>(&words).to_le_array::<2>(), [0xa5, 0x0e]);
+        let words = check_le_bytes::<33>();
+        assert_eq!(
+            in_bv::<33>(&words).to_le_array::<5>(),
+            [0xa5, 0x9e, 0xd3, 0x14, 0x01]
+        );
+        let mut words = check_le_bytes::<70>();
+        let array = in_bv::<70>(&words).to_le_array::<9>();
+        assert_eq!(out_bv::<70>(&mut words).to_le_array::<9>(), array);
+        assert_eq!(array[8], 0x3d);
+    }
+
+    #[test]
+    #[should_panic]
+    fn bv_to_le_array_wrong_len() {
+        in_bv::<33>(&[0; 2]).to_le_array::<4>();
+    }
+
+    #[test]
+    fn out_lv_masks_unused_bits() {
+        let dirty = sys::svLogicVecVal { aval: !0, bval: !0 };
+        let mut words = [dirty; 2];
+
+        out_lv::<40>(&mut words).fill(Logic::X);
+        assert_eq!((words[0].aval, words[0].bval), (!0, !0));
+        assert_eq!((words[1].aval, words[1].bval), (0xff, 0xff));
+
+        words = [dirty; 2];
+        out_lv::<40>(&mut words).set_words(&[!0, !0]);
+        assert_eq!((words[1].aval, words[1].bval), (0xff, 0));
+
+        words = [dirty; 2];
+        out_lv::<64>(&mut words).fill(Logic::Value1);
+        assert_eq!((words[1].aval, words[1].bval), (!0, 0));
+    }
+}
@@ -0,0 +1,943 @@
+use std::{ffi::c_int, marker::PhantomData};
+
+use super::*;
+
+// Dispatch an index list to the fixed-arity variant of a `sys` function when possible,
+// otherwise to the variadic one. LRM only provides fixed-arity helpers up to 3 dimensions.
+macro_rules! dispatch_index {
+    ($idx: expr, $f1: path, $f2: path, $f3: path, $fv: path; $($arg: expr),*) => {
+        match *$idx {
+            [i1] => $f1($($arg,)* i1),
+            [i1, i2] => $f2($($arg,)* i1, i2),
+            [i1, i2, i3] => $f3($($arg,)* i1, i2, i3),
+            [i1, i2, i3, i4] => $fv($($arg,)* i1, i2, i3, i4),
+            [i1, i2, i3, i4, i5] => $fv($($arg,)* i1, i2, i3, i4, i5),
+            [i1, i2, i3, i4, i5, i6] => $fv($($arg,)* i1, i2, i3, i4, i5, i6),
+            [i1, i2, i3, i4, i5, i6, i7] => $fv($($arg,)* i1, i2, i3, i4, i5, i6, i7),
+            [i1, i2, i3, i4, i5, i6, i7, i8] => $fv($($arg,)* i1, i2, i3, i4, i5, i6, i7, i8),
+            _ => panic!(
+                "open array access with {} indices is not supported (at most {})",
+                $idx.len(),
+                $crate::dpi::param::MAX_INDICES,
+            ),
+        }
+    };
+}
+
+/// Max number of unpacked dimensions supported by index-based accessors
+pub const MAX_INDICES: usize = 8;
+
+/// Common queries on open array arguments. See LRM 2023 H.12.2 (Array querying functions)
+///
+/// Dimension `0` refers to the packed part of the array,
+/// dimensions starting from `1` refer to the unpacked part, leftmost first.
+pub trait OpenArray {
+    /// The raw handle passed by the simulator
+    fn as_handle(&self) -> sys::svOpenArrayHandle;
+
+    /// See also [`sys::svLeft`]
+    fn left(&self, d: usize) -> i32 {
+        unsafe { sys::svLeft(self.as_handle(), d as c_int) }
+    }
+
+    /// See also [`sys::svRight`]
+    fn right(&self, d: usize) -> i32 {
+        unsafe { sys::svRight(self.as_handle(), d as c_int) }
+    }
+
+    /// See also [`sys::svLow`]
+    fn low(&self, d: usize) -> i32 {
+        unsafe { sys::svLow(self.as_handle(), d as c_int) }
+    }
+
+    /// See also [`sys::svHigh`]
+    fn high(&self, d: usize) -> i32 {
+        unsafe { sys::svHigh(self.as_handle(), d as c_int) }
+    }
+
+    /// `1` if `left >= right`, `-1` otherwise. See also [`sys::svIncrement`]
+    fn increment(&self, d: usize) -> i32 {
+        unsafe { sys::svIncrement(self.as_handle(), d as c_int) }
+    }
+
+    /// Number of elements in dimension `d`. See also [`sys::svSize`]
+    fn size(&self, d: usize) -> usize {
+        unsafe { sys::svSize(self.as_handle(), d as c_int) as usize }
+    }
+
+    /// Number of unpacked dimensions. See also [`sys::svDimensions`]
+    fn dimensions(&self) -> usize {
+        unsafe { sys::svDimensions(self.as_handle()) as usize }
+    }
+
+    /// Size of the whole array in bytes. See also [`sys::svSizeOfArray`]
+    fn size_of_array(&self) -> usize {
+        unsafe { sys::svSizeOfArray(self.as_handle()) as usize }
+    }
+
+    /// Indices of dimension `d` in SV order, i.e. from `left` to `right`
+    fn indices(&self, d: usize) -> Indices {
+        Indices::new(self.left(d), self.right(d))
+    }
+
+    /// Whether `index` lies in `[low, high]` of every unpacked dimension
+    ///
+    /// `index.len()` shall equal the number of unpacked dimensions, otherwise returns `false`.
+    fn contains(&self, index: &[i32]) -> bool {
+        index.len() == self.dimensions()
+            && index
+                .iter()
+                .enumerate()
+                .all(|(d, &i)| (self.low(d + 1)..=self.high(d + 1)).contains(&i))
+    }
+
+    /// Total number of elements in the unpacked part
+    fn len(&self) -> usize {
+        (1..=self.dimensions()).map(|d| self.size(d)).product()
+    }
+
+    /// Whether the array has no element
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Iterator over indices of one dimension from `left` to `right`
+#[derive(Debug, Clone)]
+pub struct Indices {
+    next: i32,
+    last: i32,
+    step: i32,
+    done: bool,
+}
+
+impl Indices {
+    fn new(left: i32, right: i32) -> Self {
+        Self {
+            next: left,
+            last: right,
+            step: if left <= right { 1 } else { -1 },
+            done: false,
+        }
+    }
+}
+
+impl Iterator for Indices {
+    type Item = i32;
+
+    fn next(&mut self) -> Option<i32> {
+        if self.done {
+            return None;
+        }
+        let cur = self.next;
+        if cur == self.last {
+            self.done = true;
+        } else {
+            self.next += self.step;
+        }
+        Some(cur)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = if self.done {
+            0
+        } else {
+            self.next.abs_diff(self.last) as usize + 1
+        };
+        (n, Some(n))
+    }
+}
+
+impl ExactSizeIterator for Indices {}
+
+/// Iterator over all element indices of an open array, in storage order
+///
+/// The rightmost dimension varies fastest, each dimension goes from `left` to `right`.
+#[derive(Debug, Clone)]
+pub struct ElemIndices {
+    // (left, right) of each unpacked dimension
+    ranges: Vec<(i32, i32)>,
+    next: Option<Vec<i32>>,
+}
+
+impl ElemIndices {
+    pub(crate) fn new<A: OpenArray + ?Sized>(array: &A) -> Self {
+        let mut indices = Self::from_ranges(
+            (1..=array.dimensions())
+                .map(|d| (array.left(d), array.right(d)))
+                .collect(),
+        );
+        // e.g. an empty dynamic array, whose `left` and `right` give no element
+        if array.is_empty() {
+            indices.next = None;
+        }
+        indices
+    }
+
+    // `(left, right)` of each unpacked dimension, both inclusive
+    fn from_ranges(ranges: Vec<(i32, i32)>) -> Self {
+        let next = (!ranges.is_empty()).then(|| ranges.iter().map(|&(left, _)| left).collect());
+        Self { ranges, next }
+    }
+}
+
+impl Iterator for ElemIndices {
+    type Item = Vec<i32>;
+
+    fn next(&mut self) -> Option<Vec<i32>> {
+        let cur = self.next.take()?;
+        let mut next = cur.clone();
+        for d in (0..self.ranges.len()).rev() {
+            let (left, right) = self.ranges[d];
+            if next[d] != right {
+                next[d] += if left <= right { 1 } else { -1 };
+                self.next = Some(next);
+                break;
+            }
+            next[d] = left;
+        }
+        Some(cur)
+    }
+}
+
+/// Get the element pointer, returns null if out of bounds
+///
+/// # Safety
+///
+/// `array` shall be an open array of basic type or packed elements
+unsafe fn elem_ptr<A: OpenArray + ?Sized>(array: &A, index: &[i32]) -> *mut c_void {
+    if !array.contains(index) {
+        return ptr::null_mut();
+    }
+    let h = array.as_handle();
+    unsafe {
+        dispatch_index!(
+            index,
+            sys::svGetArrElemPtr1,
+            sys::svGetArrElemPtr2,
+            sys::svGetArrElemPtr3,
+            sys::svGetArrElemPtr;
+            h
+        )
+    }
+}
+
+// we use supertrait sealed trait trick
+// to prevent downstream implements SvArrayElem
+trait SvArrayElemPriv {}
+
+/// Basic type stored one element per address, elements of [`InOpenArray`] and
+/// [`OutOpenArray`]. Every basic type except `bit` and `logic`, see [`SvScalar`] for those.
+#[allow(private_bounds)]
+pub trait SvArrayElem: SvBasicType + SvArrayElemPriv {}
+
+macro_rules! impl_sv_array_elem {
+    ($($T: ty),*) => {
+        $(
+            impl SvArrayElemPriv for $T {}
+            impl SvArrayElem for $T {}
+        )*
+    };
+}
+
+impl_sv_array_elem!(i8, u8, i16, u16, i32, u32, i64, u64, f64, f32);
+
+impl<T> SvArrayElemPriv for *mut T {}
+impl<T> SvArrayElem for *mut T {}
+
+impl<T: SvTransparent> SvArrayElemPriv for T where T::Repr: SvArrayElem {}
+impl<T: SvTransparent> SvArrayElem for T where T::Repr: SvArrayElem {}
+
+/// `input T name[]` open array argument, `T` is a basic type other than `bit` and `logic`.
+/// For `bit` and `logic` elements, use [`InOpenArrayScalar`] instead.
+///
+/// Indices are SV indices, as declared by the actual argument (not normalized).
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct InOpenArray<'a, T: SvArrayElem> {
+    handle: sys::svOpenArrayHandle,
+    phantom: PhantomData<&'a [T]>,
+}
+
+impl<T: SvArrayElem> OpenArray for InOpenArray<'_, T> {
+    fn as_handle(&self) -> sys::svOpenArrayHandle {
+        self.handle
+    }
+}
+
+impl<'a, T: SvArrayElem> InOpenArray<'a, T> {
+    /// Get element of a 1-dimensional array. Returns `None` if out of bounds.
+    pub fn get(&self, index: i32) -> Option<T> {
+        self.get_at(&[index])
+    }
+
+    /// Get element by indices of all unpacked dimensions. Returns `None` if out of bounds.
+    pub fn get_at(&self, index: &[i32]) -> Option<T> {
+        unsafe {
+            let ptr = elem_ptr(self, index) as *const T::Underlying;
+            (!ptr.is_null()).then(|| T::from_underlying(*ptr))
+        }
+    }
+
+    /// Iterate over all elements in storage order. See also [`ElemIndices`]
+    pub fn iter(&self) -> impl Iterator<Item = T> + use<'a, '_, T> {
+        ElemIndices::new(self).map(|index| self.get_at(&index).unwrap())
+    }
+
+    /// View the array as a slice if the simulator stores it in C layout.
+    ///
+    /// Returns `None` if [`sys::svGetArrayPtr`] returns null.
+    /// The first element of the slice is the element at `left` indices.
+    pub fn as_slice(&self) -> Option<&'a [T::Underlying]> {
+        unsafe {
+            let ptr = sys::svGetArrayPtr(self.handle) as *const T::Underlying;
+            if ptr.is_null() {
+                return None;
+            }
+            let len = self.size_of_array() / size_of::<T::Underlying>();
+            Some(std::slice::from_raw_parts(ptr, len))
+        }
+    }
+}
+
+/// `output T name[]` open array argument, `T` is a basic type other than `bit` and `logic`.
+/// For `bit` and `logic` elements, use [`OutOpenArrayScalar`] instead.
+///
+/// Indices are SV indices, as declared by the actual argument (not normalized).
+#[repr(transparent)]
+pub struct OutOpenArray<'a, T: SvArrayElem> {
+    handle: sys::svOpenArrayHandle,
+    phantom: PhantomData<&'a mut [T]>,
+}
+
+impl<T: SvArrayElem> OpenArray for OutOpenArray<'_, T> {
+    fn as_handle(&self) -> sys::svOpenArrayHandle {
+        self.handle
+    }
+}
+
+impl<T: SvArrayElem> OutOpenArray<'_, T> {
+    /// Get element of a 1-dimensional array. Returns `None` if out of bounds.
+    pub fn get(&self, index: i32) -> Option<T> {
+        self.get_at(&[index])
+    }
+
+    /// Get element by indices of all unpacked dimensions. Returns `None` if out of bounds.
+    pub fn get_at(&self, index: &[i32]) -> Option<T> {
+        unsafe {
+            let ptr = elem_ptr(self, index) as *const T::Underlying;
+            (!ptr.is_null()).then(|| T::from_underlying(*ptr))
+        }
+    }
+
+    /// Set element of a 1-dimensional array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    pub fn set(&mut self, index: i32, value: T) {
+        self.set_at(&[index], value);
+    }
+
+    /// Set element by indices of all unpacked dimensions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    pub fn set_at(&mut self, index: &[i32], value: T) {
+        unsafe {
+            let ptr = elem_ptr(self, index) as *mut T::Underlying;
+            assert!(!ptr.is_null(), "open array index {index:?} out of bounds");
+            *ptr = value.to_underlying();
+        }
+    }
+
+    /// Iterate over all elements in storage order. See also [`ElemIndices`]
+    pub fn iter(&self) -> impl Iterator<Item = T> + use<'_, T> {
+        ElemIndices::new(self).map(|index| self.get_at(&index).unwrap())
+    }
+
+    /// View the array as a slice if the simulator stores it in C layout.
+    ///
+    /// Returns `None` if [`sys::svGetArrayPtr`] returns null.
+    /// The first element of the slice is the element at `left` indices.
+    pub fn as_slice(&self) -> Option<&[T::Underlying]> {
+        unsafe {
+            let ptr = sys::svGetArrayPtr(self.handle) as *const T::Underlying;
+            if ptr.is_null() {
+                return None;
+            }
+            let len = self.size_of_array() / size_of::<T::Underlying>();
+            Some(std::slice::from_raw_parts(ptr, len))
+        }
+    }
+
+    /// Mutable version of [`Self::as_slice`]
+    pub fn as_slice_mut(&mut self) -> Option<&mut [T::Underlying]> {
+        unsafe {
+            let ptr = sys::svGetArrayPtr(self.handle) as *mut T::Underlying;
+            if ptr.is_null() {
+                return None;
+            }
+            let len = self.size_of_array() / size_of::<T::Underlying>();
+            Some(std::slice::from_raw_parts_mut(ptr, len))
+        }
+    }
+}
+
+pub type InoutOpenArray<'a, T> = OutOpenArray<'a, T>;
+
+/// # Safety
+///
+/// `array` shall be an open array of `bit [BITS-1:0]` elements
+unsafe fn get_bv<A: OpenArray + ?Sized, const BITS: usize>(
+    array: &A,
+    index: &[i32],
+) -> Option<BitVec<BITS>> {
+    if !array.contains(index) {
+        return None;
+    }
+    let mut words = vec![0; BITS.div_ceil(32)];
+    let (d, s) = (words.as_mut_ptr(), array.as_handle());
+    unsafe {
+        dispatch_index!(
+            index,
+            sys::svGetBitArrElem1VecVal,
+            sys::svGetBitArrElem2VecVal,
+            sys::svGetBitArrElem3VecVal,
+            sys::svGetBitArrElemVecVal;
+            d, s
+        )
+    }
+    Some(BitVec::from_words(&words))
+}
+
+/// # Safety
+///
+/// `array` shall be an open array of `logic [BITS-1:0]` elements
+unsafe fn get_lv<A: OpenArray + ?Sized, const BITS: usize>(
+    array: &A,
+    index: &[i32],
+) -> Option<LogicVec<BITS>> {
+    if !array.contains(index) {
+        return None;
+    }
+    let zero = sys::svLogicVecVal { aval: 0, bval: 0 };
+    let mut words = vec![zero; BITS.div_ceil(32)];
+    let (d, s) = (words.as_mut_ptr(), array.as_handle());
+    unsafe {
+        dispatch_index!(
+            index,
+            sys::svGetLogicArrElem1VecVal,
+            sys::svGetLogicArrElem2VecVal,
+            sys::svGetLogicArrElem3VecVal,
+            sys::svGetLogicArrElemVecVal;
+            d, s
+        )
+    }
+    Some(lv_to_owned(&words))
+}
+
+/// `input bit [BITS-1:0] name[]` open array argument
+///
+/// Indices are SV indices, as declared by the actual argument (not normalized).
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct InOpenArrayBV<'a, const BITS: usize> {
+    handle: sys::svOpenArrayHandle,
+    phantom: PhantomData<&'a [BitVec<BITS>]>,
+}
+
+impl<const BITS: usize> OpenArray for InOpenArrayBV<'_, BITS> {
+    fn as_handle(&self) -> sys::svOpenArrayHandle {
+        self.handle
+    }
+}
+
+impl<'a, const BITS: usize> InOpenArrayBV<'a, BITS> {
+    /// Copy element of a 1-dimensional array. Returns `None` if out of bounds.
+    pub fn get(&self, index: i32) -> Option<BitVec<BITS>> {
+        self.get_at(&[index])
+    }
+
+    /// Copy element by indices of all unpacked dimensions. Returns `None` if out of bounds.
+    ///
+    /// See also [`sys::svGetBitArrElemVecVal`]
+    pub fn get_at(&self, index: &[i32]) -> Option<BitVec<BITS>> {
+        unsafe { get_bv(self, index) }
+    }
+
+    /// View element in place, if the simulator stores it in canonical representation.
+    ///
+    /// Returns `None` if out of bounds or [`sys::svGetArrElemPtr`] returns null.
+    pub fn view_at(&self, index: &[i32]) -> Option<InBV<'a, BITS>> {
+        let ptr = unsafe { elem_ptr(self, index) };
+        (!ptr.is_null()).then_some(InBV {
+            inner: ptr as *const u32,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Iterate over all elements in storage order. See also [`ElemIndices`]
+    pub fn iter(&self) -> impl Iterator<Item = BitVec<BITS>> + use<'a, '_, BITS> {
+        ElemIndices::new(self).map(|index| self.get_at(&index).unwrap())
+    }
+}
+
+/// `output bit [BITS-1:0] name[]` open array argument
+///
+/// Indices are SV indices, as declared by the actual argument (not normalized).
+#[repr(transparent)]
+pub struct OutOpenArrayBV<'a, const BITS: usize> {
+    handle: sys::svOpenArrayHandle,
+    phantom: PhantomData<&'a mut [BitVec<BITS>]>,
+}
+
+impl<const BITS: usize> OpenArray for OutOpenArrayBV<'_, BITS> {
+    fn as_handle(&self) -> sys::svOpenArrayHandle {
+        self.handle
+    }
+}
+
+impl<const BITS: usize> OutOpenArrayBV<'_, BITS> {
+    /// Copy element of a 1-dimensional array. Returns `None` if out of bounds.
+    pub fn get(&self, index: i32) -> Option<BitVec<BITS>> {
+        self.get_at(&[index])
+    }
+
+    /// Copy element by indices of all unpacked dimensions. Returns `None` if out of bounds.
+    ///
+    /// See also [`sys::svGetBitArrElemVecVal`]
+    pub fn get_at(&self, index: &[i32]) -> Option<BitVec<BITS>> {
+        unsafe { get_bv(self, index) }
+    }
+
+    /// Set element of a 1-dimensional array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    pub fn set(&mut self, index: i32, value: &BitVec<BITS>) {
+        self.set_at(&[index], value);
+    }
+
+    /// Set element by indices of all unpacked dimensions. See also [`sys::svPutBitArrElemVecVal`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    pub fn set_at(&mut self, index: &[i32], value: &BitVec<BITS>) {
+        assert!(
+            self.contains(index),
+            "open array index {index:?} out of bounds"
+        );
+        let (d, s) = (self.handle, value.as_words().as_ptr());
+        unsafe {
+            dispatch_index!(
+                index,
+                sys::svPutBitArrElem1VecVal,
+                sys::svPutBitArrElem2VecVal,
+                sys::svPutBitArrElem3VecVal,
+                sys::svPutBitArrElemVecVal;
+                d, s
+            )
+        }
+    }
+
+    /// View element in place, if the simulator stores it in canonical representation.
+    ///
+    /// Returns `None` if out of bounds or [`sys::svGetArrElemPtr`] returns null.
+    pub fn view_mut_at(&mut self, index: &[i32]) -> Option<OutBV<'_, BITS>> {
+        let ptr = unsafe { elem_ptr(self, index) };
+        (!ptr.is_null()).then_some(OutBV {
+            inner: ptr as *mut u32,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Iterate over all elements in storage order. See also [`ElemIndices`]
+    pub fn iter(&self) -> impl Iterator<Item = BitVec<BITS>> + use<'_, BITS> {
+        ElemIndices::new(self).map(|index| self.get_at(&index).unwrap())
+    }
+}
+
+pub type InoutOpenArrayBV<'a, const BITS: usize> = OutOpenArrayBV<'a, BITS>;
+
+/// `input logic [BITS-1:0] name[]` open array argument
+///
+/// Indices are SV indices, as declared by the actual argument (not normalized).
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct InOpenArrayLV<'a, const BITS: usize> {
+    handle: sys::svOpenArrayHandle,
+    phantom: PhantomData<&'a [LogicVec<BITS>]>,
+}
+
+impl<const BITS: usize> OpenArray for InOpenArrayLV<'_, BITS> {
+    fn as_handle(&self) -> sys::svOpenArrayHandle {
+        self.handle
+    }
+}
+
+impl<'a, const BITS: usize> InOpenArrayLV<'a, BITS> {
+    /// Copy element of a 1-dimensional array. Returns `None` if out of bounds.
+    pub fn get(&self, index: i32) -> Option<LogicVec<BITS>> {
+        self.get_at(&[index])
+    }
+
+    /// Copy element by indices of all unpacked dimensions. Returns `None` if out of bounds.
+    ///
+    /// See also [`sys::svGetLogicArrElemVecVal`]
+    pub fn get_at(&self, index: &[i32]) -> Option<LogicVec<BITS>> {
+        unsafe { get_lv(self, index) }
+    }
+
+    /// View element in place, if the simulator stores it in canonical representation.
+    ///
+    /// Returns `None` if out of bounds or [`sys::svGetArrElemPtr`] returns null.
+    pub fn view_at(&self, index: &[i32]) -> Option<InLV<'a, BITS>> {
+        let ptr = unsafe { elem_ptr(self, index) };
+        (!ptr.is_null()).then_some(InLV {
+            inner: ptr as *const sys::svLogicVecVal,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Iterate over all elements in storage order. See also [`ElemIndices`]
+    pub fn iter(&self) -> impl Iterator<Item = LogicVec<BITS>> + use<'a, '_, BITS> {
+        ElemIndices::new(self).map(|index| self.get_at(&index).unwrap())
+    }
+}
+
+/// `output logic [BITS-1:0] name[]` open array argument
+///
+/// Indices are SV indices, as declared by the actual argument (not normalized).
+#[repr(transparent)]
+pub struct OutOpenArrayLV<'a, const BITS: usize> {
+    handle: sys::svOpenArrayHandle,
+    phantom: PhantomData<&'a mut [LogicVec<BITS>]>,
+}
+
+impl<const BITS: usize> OpenArray for OutOpenArrayLV<'_, BITS> {
+    fn as_handle(&self) -> sys::svOpenArrayHandle {
+        self.handle
+    }
+}
+
+impl<const BITS: usize> OutOpenArrayLV<'_, BITS> {
+    /// Copy element of a 1-dimensional array. Returns `None` if out of bounds.
+    pub fn get(&self, index: i32) -> Option<LogicVec<BITS>> {
+        self.get_at(&[index])
+    }
+
+    /// Copy element by indices of all unpacked dimensions. Returns `None` if out of bounds.
+    ///
+    /// See also [`sys::svGetLogicArrElemVecVal`]
+    pub fn get_at(&self, index: &[i32]) -> Option<LogicVec<BITS>> {
+        unsafe { get_lv(self, index) }
+    }
+
+    /// Set element of a 1-dimensional array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    pub fn set(&mut self, index: i32, value: &LogicVec<BITS>) {
+        self.set_at(&[index], value);
+    }
+
+    /// Set element by indices of all unpacked dimensions.
+    /// See also [`sys::svPutLogicArrElemVecVal`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    pub fn set_at(&mut self, index: &[i32], value: &LogicVec<BITS>) {
+        assert!(
+            self.contains(index),
+            "open array index {index:?} out of bounds"
+        );
+        let (aval, bval) = (value.aval().as_words(), value.bval().as_words());
+        let words: Vec<_> = aval
+            .iter()
+            .zip(bval)
+            .map(|(&aval, &bval)| sys::svLogicVecVal { aval, bval })
+            .collect();
+        let (d, s) = (self.handle, words.as_ptr());
+        unsafe {
+            dispatch_index!(
+                index,
+                sys::svPutLogicArrElem1VecVal,
+                sys::svPutLogicArrElem2VecVal,
+                sys::svPutLogicArrElem3VecVal,
+                sys::svPutLogicArrElemVecVal;
+                d, s
+            )
+        }
+    }
+
+    /// View element in place, if the simulator stores it in canonical representation.
+    ///
+    /// Returns `None` if out of bounds or [`sys::svGetArrElemPtr`] returns null.
+    pub fn view_mut_at(&mut self, index: &[i32]) -> Option<OutLV<'_, BITS>> {
+        let ptr = unsafe { elem_ptr(self, index) };
+        (!ptr.is_null()).then_some(OutLV {
+            inner: ptr as *mut sys::svLogicVecVal,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Iterate over all elements in storage order. See also [`ElemIndices`]
+    pub fn iter(&self) -> impl Iterator<Item = LogicVec<BITS>> + use<'_, BITS> {
+        ElemIndices::new(self).map(|index| self.get_at(&index).unwrap())
+    }
+}
+
+pub type InoutOpenArrayLV<'a, const BITS: usize> = OutOpenArrayLV<'a, BITS>;
+
+// we use supertrait sealed trait trick
+// to prevent downstream implements SvScalar
+trait SvScalarPriv {}
+
+/// Scalar `bit` or `logic`, elements of [`InOpenArrayScalar`] and [`OutOpenArrayScalar`]
+#[allow(private_bounds)]
+pub trait SvScalar: SvBasicType<Underlying = u8> + SvScalarPriv {
+    /// # Safety
+    ///
+    /// `h` shall be an open array of `Self`, and `index` shall be in bounds
+    #[doc(hidden)]
+    unsafe fn get_elem(h: sys::svOpenArrayHandle, index: &[i32]) -> u8;
+
+    /// # Safety
+    ///
+    /// Same as [`Self::get_elem`]
+    #[doc(hidden)]
+    unsafe fn put_elem(h: sys::svOpenArrayHandle, value: u8, index: &[i32]);
+}
+
+impl SvScalarPriv for bool {}
+impl SvScalar for bool {
+    unsafe fn get_elem(h: sys::svOpenArrayHandle, index: &[i32]) -> u8 {
+        unsafe {
+            dispatch_index!(
+                index,
+                sys::svGetBitArrElem1,
+                sys::svGetBitArrElem2,
+                sys::svGetBitArrElem3,
+                sys::svGetBitArrElem;
+                h
+            )
+        }
+    }
+
+    unsafe fn put_elem(h: sys::svOpenArrayHandle, value: u8, index: &[i32]) {
+        unsafe {
+            dispatch_index!(
+                index,
+                sys::svPutBitArrElem1,
+                sys::svPutBitArrElem2,
+                sys::svPutBitArrElem3,
+                sys::svPutBitArrElem;
+                h, value
+            )
+        }
+    }
+}
+
+impl SvScalarPriv for Logic {}
+impl SvScalar for Logic {
+    unsafe fn get_elem(h: sys::svOpenArrayHandle, index: &[i32]) -> u8 {
+        unsafe {
+            dispatch_index!(
+                index,
+                sys::svGetLogicArrElem1,
+                sys::svGetLogicArrElem2,
+                sys::svGetLogicArrElem3,
+                sys::svGetLogicArrElem;
+                h
+            )
+        }
+    }
+
+    unsafe fn put_elem(h: sys::svOpenArrayHandle, value: u8, index: &[i32]) {
+        unsafe {
+            dispatch_index!(
+                index,
+                sys::svPutLogicArrElem1,
+                sys::svPutLogicArrElem2,
+                sys::svPutLogicArrElem3,
+                sys::svPutLogicArrElem;
+                h, value
+            )
+        }
+    }
+}
+
+/// `input bit name[]` or `input logic name[]` open array argument, `T` is `bool` or [`Logic`]
+///
+/// Indices are SV indices, as declared by the actual argument (not normalized).
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct InOpenArrayScalar<'a, T: SvScalar> {
+    handle: sys::svOpenArrayHandle,
+    phantom: PhantomData<&'a [T]>,
+}
+
+impl<T: SvScalar> OpenArray for InOpenArrayScalar<'_, T> {
+    fn as_handle(&self) -> sys::svOpenArrayHandle {
+        self.handle
+    }
+}
+
+impl<'a, T: SvScalar> InOpenArrayScalar<'a, T> {
+    /// Get element of a 1-dimensional array. Returns `None` if out of bounds.
+    pub fn get(&self, index: i32) -> Option<T> {
+        self.get_at(&[index])
+    }
+
+    /// Get element by indices of all unpacked dimensions. Returns `None` if out of bounds.
+    ///
+    /// See also [`sys::svGetBitArrElem`] and [`sys::svGetLogicArrElem`]
+    pub fn get_at(&self, index: &[i32]) -> Option<T> {
+        self.contains(index)
+            .then(|| T::from_underlying(unsafe { T::get_elem(self.handle, index) }))
+    }
+
+    /// Iterate over all elements in storage order. See also [`ElemIndices`]
+    pub fn iter(&self) -> impl Iterator<Item = T> + use<'a, '_, T> {
+        ElemIndices::new(self).map(|index| self.get_at(&index).unwrap())
+    }
+
+    /// Copy all elements in storage order, see [`Self::iter`]
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().collect()
+    }
+}
+
+/// `output bit name[]` or `output logic name[]` open array argument, `T` is `bool` or [`Logic`]
+///
+/// Indices are SV indices, as declared by the actual argument (not normalized).
+#[repr(transparent)]
+pub struct OutOpenArrayScalar<'a, T: SvScalar> {
+    handle: sys::svOpenArrayHandle,
+    phantom: PhantomData<&'a mut [T]>,
+}
+
+impl<T: SvScalar> OpenArray for OutOpenArrayScalar<'_, T> {
+    fn as_handle(&self) -> sys::svOpenArrayHandle {
+        self.handle
+    }
+}
+
+impl<T: SvScalar> OutOpenArrayScalar<'_, T> {
+    /// Get element of a 1-dimensional array. Returns `None` if out of bounds.
+    pub fn get(&self, index: i32) -> Option<T> {
+        self.get_at(&[index])
+    }
+
+    /// Get element by indices of all unpacked dimensions. Returns `None` if out of bounds.
+    ///
+    /// See also [`sys::svGetBitArrElem`] and [`sys::svGetLogicArrElem`]
+    pub fn get_at(&self, index: &[i32]) -> Option<T> {
+        self.contains(index)
+            .then(|| T::from_underlying(unsafe { T::get_elem(self.handle, index) }))
+    }
+
+    /// Set element of a 1-dimensional array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    pub fn set(&mut self, index: i32, value: T) {
+        self.set_at(&[index], value);
+    }
+
+    /// Set element by indices of all unpacked dimensions.
+    /// See also [`sys::svPutBitArrElem`] and [`sys::svPutLogicArrElem`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds
+    pub fn set_at(&mut self, index: &[i32], value: T) {
+        assert!(
+            self.contains(index),
+            "open array index {index:?} out of bounds"
+        );
+        unsafe { T::put_elem(self.handle, value.to_underlying(), index) }
+    }
+
+    /// Iterate over all elements in storage order. See also [`ElemIndices`]
+    pub fn iter(&self) -> impl Iterator<Item = T> + use<'_, T> {
+        ElemIndices::new(self).map(|index| self.get_at(&index).unwrap())
+    }
+
+    /// Copy all elements in storage order, see [`Self::iter`]
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().collect()
+    }
+
+    /// Set all elements in storage order, see [`Self::iter`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values.len()` is not [`OpenArray::len`]
+    pub fn copy_from_slice(&mut self, values: &[T]) {
+        assert_eq!(values.len(), self.len(), "open array length mismatch");
+        for (index, &value) in ElemIndices::new(self).zip(values) {
+            unsafe { T::put_elem(self.handle, value.to_underlying(), &index) }
+        }
+    }
+}
+
+pub type InoutOpenArrayScalar<'a, T> = OutOpenArrayScalar<'a, T>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indices(ranges: &[(i32, i32)]) -> Vec<Vec<i32>> {
+        ElemIndices::from_ranges(ranges.to_vec()).collect()
+    }
+
+    #[test]
+    fn indices_descending() {
+        assert!(Indices::new(7, 0).eq((0..=7).rev()));
+        assert_eq!(Indices::new(7, 0).len(), 8);
+        let expected: Vec<_> = (0..=7).rev().map(|i| vec![i]).collect();
+        assert_eq!(indices(&[(7, 0)]), expected);
+    }
+
+    #[test]
+    fn indices_ascending() {
+        assert!(Indices::new(0, 7).eq(0..=7));
+        assert_eq!(Indices::new(0, 7).len(), 8);
+        let expected: Vec<_> = (0..=7).map(|i| vec![i]).collect();
+        assert_eq!(indices(&[(0, 7)]), expected);
+    }
+
+    #[test]
+    fn indices_single_element() {
+        let mut it = Indices::new(3, 3);
+        assert_eq!(it.len(), 1);
+        assert_eq!(it.next(), Some(3));
+        assert_eq!(it.len(), 0);
+        assert_eq!(it.next(), None);
+        assert_eq!(indices(&[(3, 3)]), [[3]]);
+        assert_eq!(indices(&[(-1, -1), (5, 5)]), [[-1, 5]]);
+    }
+
+    #[test]
+    fn indices_rightmost_fastest() {
+        // `[1:0][0:2][4:3]`
+        let all = indices(&[(1, 0), (0, 2), (4, 3)]);
+        assert_eq!(all.len(), 12);
+        assert_eq!(all[..3], [[1, 0, 4], [1, 0, 3], [1, 1, 4]]);
+        assert_eq!(all[5..7], [[1, 2, 3], [0, 0, 4]]);
+        assert_eq!(all[11], [0, 2, 3]);
+    }
+
+    #[test]
+    fn indices_no_dimension() {
+        assert!(indices(&[]).is_empty());
+    }
+}
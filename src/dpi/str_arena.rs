@@ -0,0 +1,116 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+};
+
+use super::*;
+
+/// Number of strings kept alive per thread outside of [`begin_call`]
+const CAPACITY: usize = 64;
+
+// Strings handed to the simulator, as return values or output arguments.
+// LRM Annex H only requires such strings to stay valid until the next DPI call,
+// after that the simulator owns a copy. Within a call marked by `begin_call`, every
+// string is kept, and they are dropped when the next outermost call begins. Outside of
+// such a call, e.g. in a hand-written shim, call boundaries are unknown and only the last
+// `CAPACITY` strings are kept. Either way memory use is bounded.
+thread_local! {
+    static STRINGS: RefCell<VecDeque<CString>> = const { RefCell::new(VecDeque::new()) };
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Keep `s` alive on current thread, returns pointer to its content
+pub(crate) fn store(s: CString) -> *const c_char {
+    // moving a `CString` does not move its heap buffer
+    let ptr = s.as_ptr();
+    let evicted = STRINGS.with_borrow_mut(|strings| {
+        strings.push_back(s);
+        match DEPTH.get() {
+            0 if strings.len() > CAPACITY => strings.pop_front(),
+            _ => None,
+        }
+    });
+    drop(evicted);
+    ptr
+}
+
+/// Mark the start of an import call, the call lasts until the guard is dropped.
+///
+/// Strings passed to the simulator by [`RetStr`](param::RetStr), [`OutStr`](param::OutStr)
+/// and [`InoutStr`](param::InoutStr) stay valid until the next call begins, then they are
+/// freed. Calls may nest, e.g. an import called from an export, strings are only freed when
+/// an outermost call begins.
+///
+/// `#[dpi_import]` shims call this, hand-written shims should call it first. Without it,
+/// only the last 64 strings of the thread are kept, so a call shall not pass more strings
+/// than that.
+pub fn begin_call() -> CallGuard {
+    let depth = DEPTH.get();
+    if depth == 0 {
+        // take the strings out first, so that dropping them never reenters the arena
+        let strings = STRINGS.take();
+        drop(strings);
+    }
+    DEPTH.set(depth + 1);
+    CallGuard {
+        _not_send: std::marker::PhantomData,
+    }
+}
+
+/// Created by [`begin_call`], ends the call on drop
+#[must_use = "the call ends when the guard is dropped"]
+#[derive(Debug)]
+pub struct CallGuard {
+    // the arena is per thread
+    _not_send: std::marker::PhantomData<*const ()>,
+}
+
+impl Drop for CallGuard {
+    fn drop(&mut self) {
+        DEPTH.set(DEPTH.get() - 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored() -> usize {
+        STRINGS.with_borrow(VecDeque::len)
+    }
+
+    #[test]
+    fn strings_live_until_next_call() {
+        let call = begin_call();
+        let first = store(c"first".to_owned());
+        for _ in 0..100 {
+            store(c"more".to_owned());
+        }
+        assert_eq!(unsafe { CStr::from_ptr(first) }, c"first");
+        {
+            // nested calls never free strings of the outer call
+            let _nested = begin_call();
+            store(c"nested".to_owned());
+        }
+        assert_eq!(unsafe { CStr::from_ptr(first) }, c"first");
+        drop(call);
+        assert_eq!(stored(), 102);
+
+        let _next = begin_call();
+        assert_eq!(stored(), 0);
+    }
+
+    #[test]
+    fn strings_are_bounded_without_call() {
+        drop(begin_call());
+        let first = store(c"first".to_owned());
+        for _ in 0..CAPACITY - 1 {
+            store(c"more".to_owned());
+        }
+        assert_eq!(unsafe { CStr::from_ptr(first) }, c"first");
+        for _ in 0..100 {
+            store(c"more".to_owned());
+        }
+        assert_eq!(stored(), CAPACITY);
+    }
+}
@@ -0,0 +1,256 @@
+use std::{
+    any::{TypeId, type_name},
+    marker::PhantomData,
+    sync::{
+        Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use super::*;
+
+/// Type-checked key of user data, see [`SvScope::put_user_data`]
+///
+/// Declare keys as `static`, the address of the key identifies the data. A promoted constant
+/// such as `&UserDataKey::new()` may share its address with other keys.
+pub struct UserDataKey<T: 'static> {
+    // non-zero-sized, so that every key has a distinct address
+    _unique: u8,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T: 'static> UserDataKey<T> {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        UserDataKey {
+            _unique: 0,
+            phantom: PhantomData,
+        }
+    }
+
+    fn as_raw(&'static self) -> *mut c_void {
+        self as *const Self as *mut c_void
+    }
+}
+
+// the boxed value passed to `svPutUserData`, the header is read before the value is cast to `T`
+#[repr(C)]
+struct Entry<T> {
+    type_id: TypeId,
+    borrowed: AtomicBool,
+    value: Box<T>,
+}
+
+#[repr(C)]
+struct Header {
+    type_id: TypeId,
+    borrowed: AtomicBool,
+}
+
+// values currently stored, so that they can be dropped at end of simulation.
+// The lock is also held while an entry is looked up and claimed, so that no thread frees
+// an entry another thread is claiming.
+struct Stored {
+    scope: SvScope,
+    key: usize,
+    drop: unsafe fn(*mut c_void),
+}
+
+static STORED: Mutex<Vec<Stored>> = Mutex::new(Vec::new());
+
+// a failed type check panics with the lock held, the list itself stays consistent
+fn lock() -> MutexGuard<'static, Vec<Stored>> {
+    STORED.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+unsafe fn drop_entry<T>(ptr: *mut c_void) {
+    drop(unsafe { Box::from_raw(ptr as *mut Entry<T>) });
+}
+
+// the entry of `T` at `data`, panics if the stored value is of another type
+fn checked<T: 'static>(data: *mut c_void) -> Option<NonNull<Entry<T>>> {
+    let data = NonNull::new(data)?;
+    // Safety : only `Entry` values are stored, which all start with a `Header`
+    let header = unsafe { data.cast::<Header>().as_ref() };
+    assert!(
+        header.type_id == TypeId::of::<T>(),
+        "user data is not of type `{}`",
+        type_name::<T>()
+    );
+    Some(data.cast())
+}
+
+// set the borrow flag of the entry at `data`, false if it is already set
+fn claim(data: *mut c_void) -> bool {
+    // Safety : only `Entry` values are stored, which all start with a `Header`
+    let header = unsafe { &*(data as *const Header) };
+    header
+        .borrowed
+        .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+        .is_ok()
+}
+
+fn assert_claimed<T>(claimed: bool) {
+    assert!(claimed, "user data of `{}` is borrowed", type_name::<T>());
+}
+
+impl SvScope {
+    fn get_user_data_raw(self, key: *mut c_void) -> *mut c_void {
+        unsafe { sys::svGetUserData(self.ptr.as_ptr(), key) }
+    }
+
+    fn put_user_data_raw(self, key: *mut c_void, data: *mut c_void) -> bool {
+        unsafe { sys::svPutUserData(self.ptr.as_ptr(), key, data) == 0 }
+    }
+
+    // remove the entry under `key` with the lock held, the caller frees it
+    fn take_entry<T: 'static>(
+        self,
+        stored: &mut Vec<Stored>,
+        key: &'static UserDataKey<T>,
+    ) -> Option<NonNull<Entry<T>>> {
+        let entry = checked::<T>(self.get_user_data_raw(key.as_raw()))?;
+        assert_claimed::<T>(claim(entry.as_ptr() as *mut c_void));
+        self.put_user_data_raw(key.as_raw(), ptr::null_mut());
+        stored.retain(|s| !(s.scope.ptr == self.ptr && s.key == key.as_raw() as usize));
+        Some(entry)
+    }
+
+    /// Store `value` in this scope, returns the value previously stored under `key`.
+    ///
+    /// See also [`sys::svPutUserData`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if underlying `svPutUserData` fails, if the stored value is of another type or
+    /// borrowed by [`Self::with_user_data`]
+    pub fn put_user_data<T: Send>(
+        self,
+        key: &'static UserDataKey<T>,
+        value: Box<T>,
+    ) -> Option<Box<T>> {
+        let entry = Box::new(Entry {
+            type_id: TypeId::of::<T>(),
+            borrowed: AtomicBool::new(false),
+            value,
+        });
+        let data = Box::into_raw(entry) as *mut c_void;
+        let mut stored = lock();
+        let prev = self.take_entry(&mut stored, key);
+        if !self.put_user_data_raw(key.as_raw(), data) {
+            drop(stored);
+            drop(unsafe { Box::from_raw(data as *mut Entry<T>) });
+            panic!("svPutUserData failed");
+        }
+        stored.push(Stored {
+            scope: self,
+            key: key.as_raw() as usize,
+            drop: drop_entry::<T>,
+        });
+        drop(stored);
+        prev.map(|entry| unsafe { Box::from_raw(entry.as_ptr()) }.value)
+    }
+
+    /// Remove the value stored under `key` and return it
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stored value is of another type or borrowed by [`Self::with_user_data`]
+    pub fn take_user_data<T: Send>(self, key: &'static UserDataKey<T>) -> Option<Box<T>> {
+        let entry = self.take_entry(&mut lock(), key)?;
+        Some(unsafe { Box::from_raw(entry.as_ptr()) }.value)
+    }
+
+    /// Call `f` with the value stored under `key`, returns `None` if there is no value.
+    ///
+    /// The value cannot be borrowed again, replaced or taken while `f` runs, from any thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stored value is of another type or already borrowed, e.g. when called
+    /// again from `f` with the same scope and key
+    pub fn with_user_data<T: Send, R>(
+        self,
+        key: &'static UserDataKey<T>,
+        f: impl FnOnce(&mut T) -> R,
+    ) -> Option<R> {
+        let stored = lock();
+        let entry = checked::<T>(self.get_user_data_raw(key.as_raw()))?.as_ptr();
+        assert_claimed::<T>(claim(entry as *mut c_void));
+        drop(stored);
+
+        // Safety : a claimed entry is never freed, until the claim is released
+        let borrowed = unsafe { &(*entry).borrowed };
+        struct Borrow<'a>(&'a AtomicBool);
+        impl Drop for Borrow<'_> {
+            fn drop(&mut self) {
+                self.0.store(false, Ordering::Release);
+            }
+        }
+
+        let _borrow = Borrow(borrowed);
+        Some(f(unsafe { &mut (*entry).value }))
+    }
+
+    /// Get the value stored under `key`. See also [`sys::svGetUserData`]
+    ///
+    /// Prefer [`Self::with_user_data`].
+    ///
+    /// # Safety
+    ///
+    /// The reference shall not outlive the value, i.e. the value shall not be replaced or taken
+    /// by [`Self::put_user_data`], [`Self::take_user_data`] or [`drop_all_user_data`] meanwhile.
+    /// No mutable reference from [`Self::user_data_mut`] or [`Self::with_user_data`] shall be
+    /// alive at the same time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stored value is of another type
+    pub unsafe fn user_data<'a, T: Send>(self, key: &'static UserDataKey<T>) -> Option<&'a T> {
+        let entry = checked::<T>(self.get_user_data_raw(key.as_raw()))?;
+        Some(unsafe { &(*entry.as_ptr()).value })
+    }
+
+    /// Get the value stored under `key` mutably. See also [`sys::svGetUserData`]
+    ///
+    /// Prefer [`Self::with_user_data`].
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Self::user_data`], and the reference shall be the only one to the value.
+    ///
+    /// # Panics
+    ///
+    /// Same as [`Self::user_data`]
+    pub unsafe fn user_data_mut<'a, T: Send>(
+        self,
+        key: &'static UserDataKey<T>,
+    ) -> Option<&'a mut T> {
+        let entry = checked::<T>(self.get_user_data_raw(key.as_raw()))?;
+        Some(unsafe { &mut (*entry.as_ptr()).value })
+    }
+}
+
+/// Drop all values stored by [`SvScope::put_user_data`], e.g. at end of simulation
+///
+/// Values borrowed by [`SvScope::with_user_data`] are kept.
+pub fn drop_all_user_data() {
+    let mut taken = Vec::new();
+    lock().retain(|s| {
+        let key = s.key as *mut c_void;
+        let data = s.scope.get_user_data_raw(key);
+        if data.is_null() {
+            return false;
+        }
+        if !claim(data) {
+            return true;
+        }
+        s.scope.put_user_data_raw(key, ptr::null_mut());
+        taken.push((data, s.drop));
+        false
+    });
+    // dropped without the lock, so that drop is free to store user data again
+    for (data, drop) in taken {
+        unsafe { drop(data) };
+    }
+}
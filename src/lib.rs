@@ -8,6 +8,13 @@ pub mod vpi;
 /// Raw Bindings for C headers
 pub mod sys;
 
+mod time;
+pub use time::SimTime;
+
+#[cfg(feature = "macros")]
+pub use dpi::import::{dpi_import, dpi_signature};
+
+#[allow(deprecated)]
 pub use dpi::{SvScope, set_scope, set_scope_by_name};
 
 /// Get current simulation time in _simulation time unit_.
@@ -25,3 +32,35 @@ pub fn get_time() -> u64 {
         vpi::get_time()
     }
 }
+
+/// Get current simulation time in simulation time precision.
+///
+/// Use `svGetTime` is `sv2023` feature is enabled. Otherwise, use `vpi_get_time`.
+#[cfg(any(feature = "sv2023", feature = "vpi"))]
+pub fn get_sim_time() -> SimTime {
+    #[cfg(feature = "sv2023")]
+    {
+        dpi::get_sim_time()
+    }
+
+    #[cfg(all(not(feature = "sv2023"), feature = "vpi"))]
+    {
+        vpi::get_sim_time()
+    }
+}
+
+/// Get current simulation time in the time unit of `scope`, truncated.
+///
+/// Use `svGetTime` is `sv2023` feature is enabled. Otherwise, use `vpi_get_time`.
+#[cfg(any(feature = "sv2023", feature = "vpi"))]
+pub fn get_time_in(scope: SvScope) -> SimTime {
+    #[cfg(feature = "sv2023")]
+    {
+        dpi::get_time_in(scope)
+    }
+
+    #[cfg(all(not(feature = "sv2023"), feature = "vpi"))]
+    {
+        vpi::get_time_in(scope)
+    }
+}
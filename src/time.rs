@@ -0,0 +1,197 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    time::Duration,
+};
+
+const UNITS: [(&str, i8); 6] = [
+    ("s", 0),
+    ("ms", -3),
+    ("us", -6),
+    ("ns", -9),
+    ("ps", -12),
+    ("fs", -15),
+];
+
+/// Simulation time, `ticks` in units of `10^exponent` seconds
+///
+/// `exponent` is in `-15..=2`, i.e. from `1fs` to `100s`, as `timeunit` and `timeprecision`
+/// (LRM 2023 3.14). Values in different units compare equal if they are the same time.
+#[derive(Debug, Clone, Copy)]
+pub struct SimTime {
+    ticks: u64,
+    exponent: i8,
+}
+
+impl SimTime {
+    /// # Panics
+    ///
+    /// Panics if `exponent` is not in `-15..=2`
+    pub const fn new(ticks: u64, exponent: i8) -> Self {
+        assert!(-15 <= exponent && exponent <= 2, "invalid time unit");
+        SimTime { ticks, exponent }
+    }
+
+    pub const fn ticks(self) -> u64 {
+        self.ticks
+    }
+
+    pub const fn exponent(self) -> i8 {
+        self.exponent
+    }
+
+    /// Convert into units of `10^exponent` seconds, truncated if the unit is coarser
+    ///
+    /// # Panics
+    ///
+    /// Panics if `exponent` is not in `-15..=2`, or the result overflows `u64`
+    pub fn to_unit(self, exponent: i8) -> SimTime {
+        let ticks = match self.exponent.cmp(&exponent) {
+            Ordering::Equal => self.ticks,
+            Ordering::Less => self.ticks / 10u64.pow((exponent - self.exponent) as u32),
+            Ordering::Greater => {
+                let scale = 10u64.pow((self.exponent - exponent) as u32);
+                self.ticks.checked_mul(scale).expect("time overflow")
+            }
+        };
+        SimTime::new(ticks, exponent)
+    }
+
+    /// Total femtoseconds, exact
+    pub fn as_femtos(self) -> u128 {
+        self.ticks as u128 * 10u128.pow((self.exponent + 15) as u32)
+    }
+
+    pub fn as_secs_f64(self) -> f64 {
+        self.ticks as f64 * 10f64.powi(self.exponent as i32)
+    }
+
+    /// Truncated to nanoseconds
+    ///
+    /// # Panics
+    ///
+    /// Panics if the seconds overflow `u64`, e.g. `SimTime::new(u64::MAX, 2)`
+    pub fn to_duration(self) -> Duration {
+        let nanos = self.as_femtos() / 1_000_000;
+        let secs = u64::try_from(nanos / 1_000_000_000).expect("time overflow");
+        Duration::new(secs, (nanos % 1_000_000_000) as u32)
+    }
+}
+
+impl PartialEq for SimTime {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_femtos() == other.as_femtos()
+    }
+}
+
+impl Eq for SimTime {}
+
+impl PartialOrd for SimTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SimTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_femtos().cmp(&other.as_femtos())
+    }
+}
+
+impl Hash for SimTime {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_femtos().hash(state);
+    }
+}
+
+/// In the largest unit keeping an integer part, e.g. `12.5 ns`, exact
+impl fmt::Display for SimTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let femtos = self.as_femtos();
+        let (unit, exponent) = UNITS
+            .into_iter()
+            .find(|&(_, e)| femtos >= 10u128.pow((e + 15) as u32))
+            .unwrap_or_else(|| {
+                // zero, in the unit of `self`
+                let n = UNITS.iter().position(|&(_, e)| e <= self.exponent);
+                UNITS[n.unwrap()]
+            });
+        let scale = 10u128.pow((exponent + 15) as u32);
+        write!(f, "{}", femtos / scale)?;
+        let frac = femtos % scale;
+        if frac != 0 {
+            let width = (exponent + 15) as usize;
+            let digits = format!("{frac:0width$}");
+            write!(f, ".{}", digits.trim_end_matches('0'))?;
+        }
+        write!(f, " {unit}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(SimTime::new(12_500, -12).to_string(), "12.5 ns");
+        assert_eq!(SimTime::new(125, -10).to_string(), "12.5 ns");
+        assert_eq!(SimTime::new(1, -15).to_string(), "1 fs");
+        assert_eq!(SimTime::new(1_000_001, -15).to_string(), "1.000001 ns");
+        assert_eq!(SimTime::new(3, 2).to_string(), "300 s");
+        assert_eq!(SimTime::new(999, -3).to_string(), "999 ms");
+        assert_eq!(
+            SimTime::new(u64::MAX, 2).to_string(),
+            "1844674407370955161500 s"
+        );
+    }
+
+    #[test]
+    fn display_zero_in_own_unit() {
+        assert_eq!(SimTime::new(0, -9).to_string(), "0 ns");
+        assert_eq!(SimTime::new(0, -10).to_string(), "0 ps");
+        assert_eq!(SimTime::new(0, 1).to_string(), "0 s");
+    }
+
+    #[test]
+    fn unit_conversion() {
+        let t = SimTime::new(12_345, -12);
+        assert_eq!(t.to_unit(-9), SimTime::new(12, -9));
+        assert_eq!(t.to_unit(-15).ticks(), 12_345_000);
+        assert_eq!(t, SimTime::new(12_345_000, -15));
+        assert!(t.to_unit(-9) < t);
+        assert_eq!(t.to_duration(), Duration::from_nanos(12));
+        assert_eq!(SimTime::new(5, 1).to_duration(), Duration::from_secs(50));
+    }
+
+    #[test]
+    #[should_panic = "time overflow"]
+    fn unit_conversion_overflow() {
+        SimTime::new(u64::MAX, -9).to_unit(-12);
+    }
+
+    #[test]
+    fn duration_at_limit() {
+        assert_eq!(
+            SimTime::new(u64::MAX, 0).to_duration(),
+            Duration::from_secs(u64::MAX)
+        );
+        assert_eq!(
+            SimTime::new(u64::MAX, -15).to_duration(),
+            Duration::from_nanos(u64::MAX / 1_000_000)
+        );
+    }
+
+    #[test]
+    #[should_panic = "time overflow"]
+    fn duration_overflow() {
+        SimTime::new(u64::MAX, 2).to_duration();
+    }
+
+    #[test]
+    #[should_panic = "invalid time unit"]
+    fn invalid_exponent() {
+        SimTime::new(1, 3);
+    }
+}
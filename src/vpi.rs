@@ -1,4 +1,4 @@
-use crate::sys::vpi as sys;
+use crate::{SimTime, dpi::SvScope, sys::vpi as sys};
 use std::ptr;
 
 /// Get current simulation time in _simulation time unit_
@@ -14,3 +14,34 @@ pub fn get_time() -> u64 {
     }
     ((time.high as u64) << 32) + (time.low as u64)
 }
+
+/// Simulation time precision exponent, i.e. the unit of [`get_time`].
+/// See also [`sys::vpiTimePrecision`]
+pub fn time_precision() -> i8 {
+    unsafe { sys::vpi_get(sys::vpiTimePrecision, ptr::null_mut()) as i8 }
+}
+
+/// Time unit exponent of `scope`, looked up by its name. See also [`sys::vpiTimeUnit`]
+///
+/// # Panics
+///
+/// Panics if `scope` is not found by `vpi_handle_by_name`
+pub fn time_unit(scope: SvScope) -> i8 {
+    unsafe {
+        let handle = sys::vpi_handle_by_name(scope.name().as_ptr() as *mut _, ptr::null_mut());
+        assert!(!handle.is_null(), "scope {:?} not found", scope.name());
+        let unit = sys::vpi_get(sys::vpiTimeUnit, handle);
+        sys::vpi_release_handle(handle);
+        unit as i8
+    }
+}
+
+/// Get current simulation time in simulation time precision
+pub fn get_sim_time() -> SimTime {
+    SimTime::new(get_time(), time_precision())
+}
+
+/// Get current simulation time in the time unit of `scope`, truncated
+pub fn get_time_in(scope: SvScope) -> SimTime {
+    get_sim_time().to_unit(time_unit(scope))
+}
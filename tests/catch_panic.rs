@@ -0,0 +1,26 @@
+#![cfg(feature = "macros")]
+
+use svdpi::{dpi::import::SvType, dpi_import, dpi_signature};
+
+#[dpi_import(catch_panic)]
+fn checked_div(a: u32, b: u32) -> u32 {
+    a / b
+}
+
+#[dpi_import(catch_panic, context)]
+fn describe(value: u32) -> String {
+    format!("value {value}")
+}
+
+// the wrapped shim keeps the signature
+#[test]
+fn signature_is_unchanged() {
+    assert_eq!(checked_div(6, 3), 2);
+    assert_eq!(describe(1), "value 1");
+    assert_eq!(
+        dpi_signature!(checked_div).sv_ret,
+        Some(SvType::basic::<u32>())
+    );
+    assert!(dpi_signature!(describe).context);
+    assert_eq!(dpi_signature!(describe).sv_ret, Some(SvType::String));
+}
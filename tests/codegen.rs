@@ -0,0 +1,76 @@
+#![cfg(feature = "macros")]
+
+use svdpi::dpi::{
+    BitVec,
+    codegen::SvPackage,
+    import::{DpiSignature, SvType, registered_signatures},
+};
+use svdpi::{dpi_import, dpi_signature};
+
+#[dpi_import]
+fn scale(value: u32, factor: u32, out: &mut BitVec<40>) -> bool {
+    *out = BitVec::from(value as u64 * factor as u64);
+    out.bit(32)
+}
+
+#[dpi_import(name = "add_c", context)]
+fn add(a: i32, b: i32) -> i32 {
+    a.wrapping_add(b)
+}
+
+mod nested {
+    use super::*;
+
+    #[dpi_import]
+    pub fn nibble(value: u8) -> BitVec<4> {
+        BitVec::from(value & 0xf)
+    }
+}
+
+#[test]
+fn imports_are_registered() {
+    let mut names: Vec<_> = registered_signatures().map(|f| f.name).collect();
+    names.sort();
+    assert_eq!(names, ["add_c", "nibble", "scale"]);
+}
+
+#[test]
+fn registered_package() {
+    let package = SvPackage::registered("pkg");
+    let by_hand = SvPackage::new("pkg")
+        .function(dpi_signature!(add))
+        .functions([dpi_signature!(nested::nibble), dpi_signature!(scale)]);
+    assert_eq!(package.to_sv(), by_hand.to_sv());
+    assert_eq!(package.to_c_header(), by_hand.to_c_header());
+
+    let sv = package.to_sv();
+    let lines: Vec<_> = sv.lines().filter(|l| l.contains("import")).collect();
+    assert_eq!(
+        lines,
+        [
+            "  import \"DPI-C\" context function int add_c(input int a, input int b);",
+            "  import \"DPI-C\" function bit [3:0] nibble(input byte unsigned value);",
+            "  import \"DPI-C\" function bit scale(input int unsigned value, \
+             input int unsigned factor, output bit [39:0] out);",
+        ]
+    );
+    assert!(
+        package
+            .to_c_header()
+            .contains("\nsvBitVecVal nibble(unsigned char value);\n")
+    );
+}
+
+#[test]
+#[should_panic = "function `bad` returns"]
+fn c_header_rejects_invalid_result() {
+    let bad = DpiSignature {
+        name: "bad",
+        context: false,
+        params: &[],
+        ret: Some("LogicVec<8>"),
+        sv_ret: Some(SvType::LogicVec(8)),
+        task: false,
+    };
+    SvPackage::new("pkg").function(&bad).to_c_header();
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "macros")]
+
+// The expansions shall compile, the generated shims are not called without a simulator
+
+use svdpi::dpi::{
+    BitVec, Logic, LogicVec,
+    export::Disabled,
+    import::{Direction, SvType},
+    param::{InBV, OutOpenArrayBV},
+};
+use svdpi::{dpi_import, dpi_signature};
+
+#[dpi_import]
+fn alu(sub: bool, a: u32, b: u32, carry: &mut bool) -> u32 {
+    let (value, overflow) = match sub {
+        false => a.overflowing_add(b),
+        true => a.overflowing_sub(b),
+    };
+    *carry = overflow;
+    value
+}
+
+#[dpi_import(name = "mem_read_c", context)]
+fn mem_read(addr: u64, #[inout] data: &mut BitVec<72>, mask: LogicVec<8>) -> i32 {
+    let _ = (addr, data, mask);
+    0
+}
+
+#[dpi_import]
+fn greet(who: &str) -> String {
+    format!("hello {who}")
+}
+
+#[dpi_import]
+fn fill(value: InBV<'_, 16>, mut out: OutOpenArrayBV<'_, 16>) -> Result<(), Disabled> {
+    let _ = (value, &mut out);
+    Ok(())
+}
+
+#[dpi_import]
+fn is_known(value: LogicVec<4>) -> Option<bool> {
+    value.to_bitvec().map(|v| !v.is_zero())
+}
+
+#[dpi_import]
+fn parity(value: BitVec<72>) -> BitVec<1> {
+    BitVec::from(value.count_ones() % 2 == 1)
+}
+
+#[dpi_import]
+fn low_byte(value: u32) -> BitVec<8> {
+    BitVec::from(value as u8)
+}
+
+#[test]
+fn import_calls_the_function() {
+    let mut carry = false;
+    assert_eq!(alu(true, 1, 2, &mut carry), u32::MAX);
+    assert!(carry);
+    assert_eq!(greet("sv"), "hello sv");
+    assert_eq!(low_byte(0x1234), BitVec::from(0x34u8));
+}
+
+#[test]
+fn import_signatures() {
+    let alu = dpi_signature!(alu);
+    assert_eq!(alu.name, "alu");
+    assert!(!alu.context);
+    let directions: Vec<_> = alu.params.iter().map(|p| p.direction).collect();
+    assert_eq!(
+        directions,
+        [
+            Direction::Input,
+            Direction::Input,
+            Direction::Input,
+            Direction::Output
+        ]
+    );
+    assert_eq!(alu.params[0].sv_type, SvType::basic::<bool>());
+    assert_eq!(alu.sv_ret, Some(SvType::basic::<u32>()));
+
+    let mem_read = dpi_signature!(mem_read);
+    assert_eq!(mem_read.name, "mem_read_c");
+    assert!(mem_read.context);
+    assert_eq!(mem_read.params[1].direction, Direction::Inout);
+    assert_eq!(mem_read.params[1].sv_type, SvType::BitVec(72));
+    assert_eq!(mem_read.params[2].sv_type, SvType::LogicVec(8));
+
+    assert_eq!(dpi_signature!(greet).sv_ret, Some(SvType::String));
+    assert!(dpi_signature!(fill).task);
+    assert_eq!(
+        dpi_signature!(is_known).sv_ret,
+        Some(SvType::basic::<Logic>())
+    );
+    assert_eq!(dpi_signature!(parity).sv_ret, Some(SvType::BitVec(1)));
+    assert_eq!(dpi_signature!(low_byte).sv_ret, Some(SvType::BitVec(8)));
+}
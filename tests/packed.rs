@@ -0,0 +1,88 @@
+#![cfg(feature = "macros")]
+
+use svdpi::dpi::{
+    BitVec,
+    packed::{SvPacked, SvPackedField},
+};
+
+#[derive(SvPacked, Debug, PartialEq)]
+struct Inner {
+    #[bits(4)]
+    a: u8,
+}
+
+#[derive(SvPacked, Debug, PartialEq)]
+struct Outer {
+    hi: u8,
+    inner: Inner,
+    lo: u8,
+}
+
+#[derive(SvPacked, Debug, PartialEq)]
+struct Mixed {
+    flag: bool,
+    #[bits(3)]
+    delta: i8,
+    wide: BitVec<40>,
+    #[bits(20)]
+    narrow: BitVec<33>,
+    word: u32,
+}
+
+#[derive(SvPacked, Debug, PartialEq)]
+struct Braced {
+    #[bits(6)]
+    value: BitVec<{ 4 + 4 }>,
+    tag: BitVec<{ 2 * 2 }>,
+}
+
+#[test]
+fn braced_const_width() {
+    assert_eq!(Braced::WIDTH, 10);
+    let braced = Braced {
+        value: BitVec::from(0x2au8),
+        tag: BitVec::from(0x5u8),
+    };
+    let bv = braced.to_bitvec::<10>();
+    assert_eq!(bv, BitVec::from(0x2a5u16));
+    assert_eq!(Braced::from_bitvec(&bv), braced);
+}
+
+#[test]
+fn nested_layout() {
+    assert_eq!(Outer::WIDTH, 20);
+    let outer = Outer {
+        hi: 0xaa,
+        inner: Inner { a: 0xf },
+        lo: 0x11,
+    };
+    let bv = outer.to_bitvec::<20>();
+    assert_eq!(bv, BitVec::from(0xaaf11u32));
+    assert_eq!(Outer::from_bitvec(&bv), outer);
+}
+
+#[test]
+fn narrowed_fields_are_masked() {
+    let outer = Outer {
+        hi: 0,
+        inner: Inner { a: 0xff },
+        lo: 0,
+    };
+    assert_eq!(outer.to_bitvec::<20>(), BitVec::from(0xf00u32));
+}
+
+#[test]
+fn mixed_layout() {
+    assert_eq!(Mixed::WIDTH, 1 + 3 + 40 + 20 + 32);
+    let mixed = Mixed {
+        flag: true,
+        delta: -3,
+        wide: BitVec::from(0xab_1234_5678u64),
+        narrow: BitVec::from(0x9_8765u32),
+        word: 0xdead_beef,
+    };
+    let bv = mixed.to_bitvec::<96>();
+    let expected = 1u128 << 95 | 0b101 << 92 | 0xab_1234_5678 << 52 | 0x9_8765 << 32 | 0xdead_beef;
+    assert_eq!(bv, BitVec::from_u128(expected));
+    assert_eq!(Mixed::from_bitvec(&bv), mixed);
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "macros")]
+
+use svdpi::dpi::{
+    import::SvType,
+    param::{SvEnum, SvNewtype, SvTransparent},
+};
+use svdpi::{dpi_import, dpi_signature};
+
+// the shims report invalid encodings in debug builds, which needs a simulator to link
+mod simulator {
+    use std::ffi::{c_char, c_int, c_void};
+
+    #[unsafe(no_mangle)]
+    extern "C" fn svGetScope() -> *mut c_void {
+        std::ptr::null_mut()
+    }
+
+    #[unsafe(no_mangle)]
+    extern "C" fn svGetNameFromScope(_: *mut c_void) -> *const c_char {
+        c"".as_ptr()
+    }
+
+    #[unsafe(no_mangle)]
+    extern "C" fn svGetCallerInfo(_: *mut *const c_char, _: *mut c_int) -> c_int {
+        0
+    }
+}
+
+#[derive(SvEnum, Clone, Copy, Debug, PartialEq)]
+#[repr(u8)]
+enum Op {
+    Add,
+    Sub = 3,
+}
+
+#[derive(SvEnum, Clone, Copy, Debug, PartialEq)]
+#[repr(i32)]
+enum Status {
+    Ok = 1,
+    #[fallback]
+    Error = -1,
+}
+
+#[derive(SvNewtype, Clone, Copy, Debug, PartialEq)]
+#[repr(transparent)]
+struct Addr(u64);
+
+#[dpi_import]
+fn access(op: Op, addr: Addr, next: &mut Addr) -> Status {
+    *next = Addr(addr.0 + 1);
+    match op {
+        Op::Add => Status::Ok,
+        Op::Sub => Status::Error,
+    }
+}
+
+#[test]
+fn passed_as_repr() {
+    let access = dpi_signature!(access);
+    assert_eq!(access.params[0].sv_type, SvType::basic::<u8>());
+    assert_eq!(access.params[1].sv_type, SvType::basic::<u64>());
+    assert_eq!(access.params[2].sv_type, SvType::basic::<u64>());
+    assert_eq!(access.sv_ret, Some(SvType::basic::<i32>()));
+}
+
+#[test]
+fn enum_encodings() {
+    assert_eq!(Op::try_from_repr(3), Some(Op::Sub));
+    assert_eq!(Op::try_from_repr(1), None);
+    assert_eq!(Op::Sub.to_repr(), 3);
+    assert_eq!(Status::from_repr(7), Status::Error);
+    assert_eq!(Status::from_repr(0), Status::Error);
+    assert_eq!(Addr::from_repr(5), Addr(5));
+    assert_eq!(Addr(5).to_repr(), 5);
+}
+
+#[test]
+#[should_panic]
+fn enum_invalid_encoding_panics() {
+    Op::from_repr(1);
+}